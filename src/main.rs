@@ -1,23 +1,39 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use eframe::egui;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
 use std::f32::consts::PI;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
 
 fn main() -> Result<(), eframe::Error> {
-    let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([900.0, 700.0])
-            .with_resizable(true),
-        ..Default::default()
-    };
+    // --test-mode starts from the default wheel with a fixed seed, nothing gets saved
+    let test_mode = std::env::args().any(|arg| arg == "--test-mode");
+    let saved_window = if test_mode { None } else { WheelApp::saved_window_geometry() };
+    let mut viewport = egui::ViewportBuilder::default().with_inner_size([900.0, 700.0]).with_resizable(true);
+    if let Some(window) = &saved_window {
+        viewport = viewport.with_inner_size([window.width, window.height]).with_position([window.x, window.y]);
+    }
+    let options = eframe::NativeOptions { viewport, ..Default::default() };
 
     eframe::run_native(
         "Gerbil Decide",
         options,
-        Box::new(|_cc| Ok(Box::new(WheelApp::load()))),
+        Box::new(move |_cc| {
+            let app = if test_mode {
+                WheelApp::new_for_test(vec![Wheel::new("Wheel 1".to_string())], 0)
+            } else {
+                WheelApp::load()
+            };
+            Ok(Box::new(app))
+        }),
     )
 }
 
@@ -26,34 +42,413 @@ struct Item {
     name: String,
     #[serde(default = "default_weight")]
     weight: u32,
+    #[serde(default)]
+    protected: bool,
+    #[serde(default)]
+    image_path: Option<String>,
+    // spins to sit out after winning, None uses the wheel's setting
+    #[serde(default)]
+    cooldown: Option<u32>,
+    #[serde(default)]
+    cooldown_left: u32,
+    // picked slice colour, None uses the normal rainbow
+    #[serde(default)]
+    color: Option<[u8; 3]>,
+    // where it sat in the list before being removed, for putting it back in place
+    #[serde(default)]
+    removed_from: Option<usize>,
+    // every win counts, even when the winner gets removed and put back later
+    #[serde(default)]
+    win_count: u32,
+    // shown when hovering the slice, empty means nothing pops up
+    #[serde(default)]
+    notes: String,
+    // one spin multiplier, never saved and reset once the spin finishes
+    #[serde(skip, default = "default_boost")]
+    boost: u32,
+    // ticked in the list for a subset draw, not saved either
+    #[serde(skip)]
+    selected: bool,
+}
+
+fn default_boost() -> u32 {
+    1
 }
 
+// what the boost button cycles through
+const MAX_BOOST: u32 = 5;
+
+// keys you can bind the boost to, function keys so they don't fight with typing
+const BOOST_HOTKEYS: [egui::Key; 12] = [
+    egui::Key::F1,
+    egui::Key::F2,
+    egui::Key::F3,
+    egui::Key::F4,
+    egui::Key::F5,
+    egui::Key::F6,
+    egui::Key::F7,
+    egui::Key::F8,
+    egui::Key::F9,
+    egui::Key::F10,
+    egui::Key::F11,
+    egui::Key::F12,
+];
+
 fn default_weight() -> u32 {
     1
 }
 
 impl Item {
     fn new(name: String) -> Self {
-        Self { name, weight: 1 }
+        Self {
+            name,
+            weight: 1,
+            protected: false,
+            image_path: None,
+            cooldown: None,
+            cooldown_left: 0,
+            color: None,
+            removed_from: None,
+            win_count: 0,
+            notes: String::new(),
+            boost: 1,
+            selected: false,
+        }
+    }
+
+    fn cycle_boost(&mut self) {
+        self.boost = if self.boost >= MAX_BOOST { 1 } else { self.boost + 1 };
+    }
+
+    fn effective_weight(&self) -> u32 {
+        self.weight.saturating_mul(self.boost)
+    }
+}
+
+// really old (or hand written) saves just have a list of names
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum ItemOrName {
+    Name(String),
+    Item(Item),
+}
+
+fn deserialize_items<'de, D>(deserializer: D) -> Result<Vec<Item>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw_items: Vec<ItemOrName> = serde::Deserialize::deserialize(deserializer)?;
+    let items = raw_items
+        .into_iter()
+        .map(|raw_item| match raw_item {
+            ItemOrName::Name(name) => Item::new(name),
+            ItemOrName::Item(item) => item,
+        })
+        .collect();
+    Ok(items)
+}
+
+// one result, at is unix seconds and 0 for results from before times were kept
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct HistoryEntry {
+    name: String,
+    #[serde(default)]
+    at: i64,
+    // how many ticked items a spin selected picked from, name stays the bare item name
+    #[serde(default)]
+    subset_of: Option<usize>,
+}
+
+impl HistoryEntry {
+    fn now(name: String) -> Self {
+        Self { name, at: chrono::Utc::now().timestamp(), subset_of: None }
+    }
+
+    // what the history list and exports show
+    fn label(&self) -> String {
+        match self.subset_of {
+            Some(subset_size) => format!("{} (subset of {})", self.name, subset_size),
+            None => self.name.clone(),
+        }
+    }
+
+    // local time, or nothing for the old untimed ones
+    fn local_time(&self) -> Option<String> {
+        if self.at == 0 {
+            return None;
+        }
+        let utc_time = chrono::DateTime::from_timestamp(self.at, 0)?;
+        Some(utc_time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
     }
 }
 
+// older saves just have the winner names
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum EntryOrName {
+    Name(String),
+    Entry(HistoryEntry),
+}
+
+fn deserialize_history<'de, D>(deserializer: D) -> Result<Vec<HistoryEntry>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw_entries: Vec<EntryOrName> = serde::Deserialize::deserialize(deserializer)?;
+    let history = raw_entries
+        .into_iter()
+        .map(|raw_entry| match raw_entry {
+            EntryOrName::Name(name) => HistoryEntry { name, at: 0, subset_of: None },
+            EntryOrName::Entry(entry) => entry,
+        })
+        .collect();
+    Ok(history)
+}
+
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 
 // probably a better way to do this but oh well for now
 struct WheelData {
     name: String,
+    #[serde(deserialize_with = "deserialize_items")]
     items: Vec<Item>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_items")]
     removed_items: Vec<Item>,
-    #[serde(default)]
-    winner_history: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_history")]
+    winner_history: Vec<HistoryEntry>,
     #[serde(default)]
     remove_winner: bool,
     #[serde(default)]
     auto_spin: bool,
+    #[serde(default)]
+    heatmap_colors: bool,
+    #[serde(default)]
+    scale_on_add: bool,
+    #[serde(default)]
+    min_elimination_items: u32,
+    #[serde(default)]
+    sort_mode: SortMode,
+    #[serde(default)]
+    view_only_sort: bool,
+    #[serde(default)]
+    boost_hotkey: Option<String>,
+    #[serde(default)]
+    boost_hotkey_item: Option<String>,
+    #[serde(default = "default_slice_stroke_width")]
+    slice_stroke_width: f32,
+    #[serde(default = "default_slice_stroke_color")]
+    slice_stroke_color: [u8; 3],
+    // hidden from the tab strip, still reachable from the archived menu
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    shuffle_slices: bool,
+    // how many spins a winner sits out, 0 is off
+    #[serde(default)]
+    winner_cooldown: u32,
+    #[serde(default)]
+    no_immediate_repeats: bool,
+    // named snapshots of every item's weight, matched back up by list position
+    #[serde(default)]
+    weight_presets: Vec<(String, Vec<u32>)>,
+    // text or an emoji drawn on the hub, plain dot when unset
+    #[serde(default)]
+    center_label: Option<String>,
+    // results wait for accept / reject before they count
+    #[serde(default)]
+    confirm_winner: bool,
+    // weight new items start at, 0 means an equal share like before
+    #[serde(default)]
+    default_item_weight: u32,
+    #[serde(default)]
+    restore_in_place: bool,
+    // same seed gives the same run of spins on anyone's copy, overrides the app wide rng
+    #[serde(default)]
+    seed: Option<u64>,
+    // this wheel only, still spins and removes winners but the list can't be touched
+    #[serde(default)]
+    locked: bool,
+    // the number box next to each item edits the raw weight instead of the percentage
+    #[serde(default)]
+    weight_mode: bool,
+    // winners drawn per spin, the extras come from the same seed. 0 and 1 are both one
+    #[serde(default)]
+    pick_count: u32,
+    #[serde(default)]
+    spin_length: SpinLength,
+    #[serde(default)]
+    pointer_pos: PointerPos,
+    // the wheel has always turned anticlockwise on screen, this flips it
+    #[serde(default)]
+    clockwise: bool,
+    // off means adding a name that's already on the wheel (any case) gets turned away
+    #[serde(default)]
+    allow_duplicates: bool,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+enum SortMode {
+    #[default]
+    Manual,
+    NameAscending,
+    WeightDescending,
+}
+
+impl SortMode {
+    fn label(self) -> &'static str {
+        match self {
+            SortMode::Manual => "Manual",
+            SortMode::NameAscending => "Name (A-Z)",
+            SortMode::WeightDescending => "Weight (high-low)",
+        }
+    }
+}
+
+// how long the wheel keeps going, only the starting speed and the slowdown change so
+// where it stops is just as random either way
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+enum SpinLength {
+    Short,
+    #[default]
+    Medium,
+    Long,
+}
+
+impl SpinLength {
+    fn label(self) -> &'static str {
+        match self {
+            SpinLength::Short => "Short",
+            SpinLength::Medium => "Medium",
+            SpinLength::Long => "Long",
+        }
+    }
+
+    // starting speed in radians per frame
+    fn velocity_range(self) -> std::ops::Range<f32> {
+        match self {
+            SpinLength::Short => 0.4..0.65,
+            SpinLength::Medium => 0.5..0.8,
+            SpinLength::Long => 0.6..0.95,
+        }
+    }
+
+    // speed kept each frame
+    fn decay(self) -> f32 {
+        match self {
+            SpinLength::Short => 0.96,
+            SpinLength::Medium => 0.975,
+            SpinLength::Long => 0.985,
+        }
+    }
+}
+
+// which edge of the wheel the arrow points in from
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+enum PointerPos {
+    #[default]
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl PointerPos {
+    fn label(self) -> &'static str {
+        match self {
+            PointerPos::Top => "Top",
+            PointerPos::Right => "Right",
+            PointerPos::Bottom => "Bottom",
+            PointerPos::Left => "Left",
+        }
+    }
+
+    // screen angle of the arrow from the middle of the wheel, y goes down so top is -PI/2
+    fn angle(self) -> f32 {
+        match self {
+            PointerPos::Top => -PI / 2.0,
+            PointerPos::Right => 0.0,
+            PointerPos::Bottom => PI / 2.0,
+            PointerPos::Left => PI,
+        }
+    }
+}
+
+fn default_slice_stroke_width() -> f32 {
+    2.0
+}
+
+fn default_slice_stroke_color() -> [u8; 3] {
+    [255, 255, 255]
+}
+
+impl WheelData {
+    // sign on everything that turns the wheel. slices are drawn at angle - rotation, so a
+    // growing rotation goes anticlockwise and a shrinking one clockwise
+    fn spin_direction(&self) -> f32 {
+        if self.clockwise { -1.0 } else { 1.0 }
+    }
+
+    fn slice_stroke(&self) -> egui::Stroke {
+        if self.slice_stroke_width <= 0.0 {
+            return egui::Stroke::NONE;
+        }
+        let [red, green, blue] = self.slice_stroke_color;
+        egui::Stroke::new(self.slice_stroke_width, egui::Color32::from_rgb(red, green, blue))
+    }
+}
+
+// one spin in an event archive, with the starting wheel that's enough to redo it exactly
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct ArchivedSpin {
+    seed: u64,
+    winner: String,
+    timestamp: i64,
+    // instant draws pick straight from the seed, the rest spin the wheel with it
+    instant: bool,
+    // names a spin selected was limited to, empty for a normal spin
+    #[serde(default)]
+    subset: Vec<String>,
+    // certified draws pick with ChaCha20 and only ever take the one winner
+    #[serde(default)]
+    certified: bool,
 }
 
+// a whole recorded event: the wheel as it was when recording started and every spin after
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct EventArchive {
+    started_utc: String,
+    initial_wheel: WheelData,
+    spins: Vec<ArchivedSpin>,
+}
+
+// an archive being played back, keeps score against the recorded winners
+#[derive(Default)]
+struct EventReplay {
+    upcoming: VecDeque<ArchivedSpin>,
+    current: Option<ArchivedSpin>,
+    matched: usize,
+    mismatched: usize,
+    pause: f32,
+}
+
+// runs a whole archive on a scratch copy of its wheel, as (matched, mismatched) winners
+fn verify_event_archive(event_archive: &EventArchive) -> (usize, usize) {
+    let mut replay_wheel = Wheel::for_replay(event_archive);
+    let mut scratch_rng = SpinRng::default();
+    loop {
+        if replay_wheel.state.is_spinning {
+            replay_wheel.tick(1.0, &mut scratch_rng);
+        } else if !replay_wheel.advance_replay(REPLAY_PAUSE_SECONDS, &mut scratch_rng) {
+            break;
+        }
+    }
+    let replay = replay_wheel.state.replay.unwrap_or_default();
+    (replay.matched, replay.mismatched)
+}
+
+// gap between replayed spins so each result can be seen
+const REPLAY_PAUSE_SECONDS: f32 = 1.5;
+
 #[derive(Default)]
 struct WheelState {
     input_text: String,
@@ -65,13 +460,155 @@ struct WheelState {
     editing_idx: Option<usize>,
     edit_buf: String,
     pct_bufs: Vec<String>,
+    queue_input: String,
+    spin_queue: VecDeque<String>,
+    active_queue_label: Option<String>,
+    queue_pause: Option<f32>,
+    queue_results: Vec<(String, String)>,
+    cleared_stash: Option<ClearedStash>,
+    pinned_winner: Option<String>,
+    elimination_allowed: bool,
+    image_edit_idx: Option<usize>,
+    image_path_buf: String,
+    notes_edit_idx: Option<usize>,
+    // only list rows whose name has this in it, the wheel always uses every item
+    item_filter: String,
+    // item indices in the order they're drawn round the wheel, empty means list order
+    slice_order: Vec<usize>,
+    preset_name_input: String,
+    spin_undo: Vec<SpinUndo>,
+    // where the wheel stopped while waiting to be accepted, name kept to find it again
+    provisional_winner: Option<(usize, String)>,
+    json_import_buf: String,
+    bulk_input_text: String,
+    what_if_weight: u32,
+    // seed the current (or last) spin was drawn with, for event archives
+    spin_seed: u64,
+    spin_was_instant: bool,
+    // only set for the one result a certified draw finishes, the archive needs to know
+    spin_was_certified: bool,
+    event_recording: Option<EventArchive>,
+    replay: Option<EventReplay>,
+    // snapshots from before each edit, per wheel so switching doesn't mix them up
+    undo_stack: Vec<WheelData>,
+    redo_stack: Vec<WheelData>,
+    seed_input: String,
+    // where the wheel seed has got to, None means the next spin is the first one
+    seed_sequence: Option<u64>,
+    landed_layout: Option<LandedLayout>,
+    // shown under the add box when the typed name is already on the wheel
+    duplicate_warning: Option<String>,
+}
+
+// the items and slice weights the last spin landed on. cooldowns and boost resets change the
+// slices right after a win, so the wheel keeps showing this until the next spin or an edit,
+// otherwise the arrow ends up over some other item
+#[derive(Clone)]
+struct LandedLayout {
+    item_signature: Vec<(String, u32)>,
+    slice_weights: Vec<u32>,
+}
+
+// whatever "Clear All" threw away, kept until the next edit
+struct ClearedStash {
+    items: Vec<Item>,
+    winner_history: Vec<HistoryEntry>,
 }
 
+// one finished spin, enough to take it back: the history line and where the winner
+// sat if it got removed
+struct SpinUndo {
+    winner_name: String,
+    // what went into the history, all the names for a multi pick
+    history_entry: String,
+    history_subset: Option<usize>,
+    other_picks: Vec<String>,
+    removed_at: Option<usize>,
+    // sit out counters from before the spin, in list order with the winner still in
+    cooldowns_before: Vec<u32>,
+    // spin selected doesn't add to win counts, so its undo mustn't take one off
+    counted_wins: bool,
+}
+
+// edit snapshots kept per wheel
+const MAX_EDIT_UNDO: usize = 50;
+
+// how many spins back undo can go, a long elimination run fits easily
+const MAX_SPIN_UNDO: usize = 100;
+
+// seconds to wait between queued spins so you can actually see each result
+const QUEUE_PAUSE_SECONDS: f32 = 1.5;
+
 struct Wheel {
     data: WheelData,
     state: WheelState,
 }
 
+// accepts stuff like "25", "25 %", "%25" and "1,5" (comma decimals)
+fn parse_pct(raw_input: &str) -> Option<f32> {
+    let without_spaces: String = raw_input.chars().filter(|c| !c.is_whitespace()).collect();
+    let without_percent = without_spaces.trim_start_matches('%').trim_end_matches('%');
+    let normalized = without_percent.replace(',', ".");
+    match normalized.parse::<f32>() {
+        Ok(value) if value.is_finite() => Some(value),
+        _ => None,
+    }
+}
+
+// "3" is a plain weight, "2d6" / "d20+1" is the average roll and "1:4" is one in
+// four as a percentage, so a wheel of ratios lines up
+fn parse_weight_notation(notation: &str) -> Option<u32> {
+    let notation: String = notation.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+    if let Some((chance_part, out_of_part)) = notation.split_once(':') {
+        let chance: u32 = chance_part.parse().ok()?;
+        let out_of: u32 = out_of_part.parse().ok()?;
+        if chance == 0 || out_of == 0 || chance > out_of {
+            return None;
+        }
+        return Some(((chance as f64 * 100.0 / out_of as f64).round() as u32).max(1));
+    }
+    if let Some((count_part, rest)) = notation.split_once('d') {
+        let dice_count: u32 = if count_part.is_empty() { 1 } else { count_part.parse().ok()? };
+        let (sides_part, modifier) = match rest.find(['+', '-']) {
+            Some(sign_index) => (&rest[..sign_index], rest[sign_index..].parse::<i64>().ok()?),
+            None => (rest, 0),
+        };
+        let sides: u32 = sides_part.parse().ok()?;
+        if !(1..=100).contains(&dice_count) || !(1..=1000).contains(&sides) {
+            return None;
+        }
+        let average_roll = dice_count as f64 * (sides as f64 + 1.0) / 2.0 + modifier as f64;
+        if average_roll < 0.5 {
+            return None;
+        }
+        return Some(average_roll.round() as u32);
+    }
+    let weight: u32 = notation.parse().ok()?;
+    if weight == 0 { None } else { Some(weight) }
+}
+
+// splits "sword (2d6)" into the name and the bit in brackets, only when the
+// brackets start with a digit (or d and a digit) so normal names with brackets are left alone
+fn split_weight_notation(raw_input: &str) -> Option<(&str, &str)> {
+    let without_close = raw_input.strip_suffix(')')?;
+    let open_index = without_close.rfind('(')?;
+    let name = without_close[..open_index].trim();
+    let notation = without_close[open_index + 1..].trim();
+    let after_die = notation.strip_prefix(['d', 'D']).unwrap_or(notation);
+    let looks_like_notation = after_die.starts_with(|c: char| c.is_ascii_digit());
+    if name.is_empty() || !looks_like_notation {
+        return None;
+    }
+    Some((name, notation))
+}
+
+// stop scaling weights up on add once they'd get silly big
+const MAX_SCALED_TOTAL_WEIGHT: u32 = 1_000_000;
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
 // wheel items and data
 
 impl Wheel {
@@ -97,6 +634,32 @@ impl Wheel {
                 winner_history: Vec::new(),
                 remove_winner: false,
                 auto_spin: false,
+                heatmap_colors: false,
+                scale_on_add: false,
+                min_elimination_items: 0,
+                sort_mode: SortMode::Manual,
+                view_only_sort: false,
+                boost_hotkey: None,
+                boost_hotkey_item: None,
+                slice_stroke_width: default_slice_stroke_width(),
+                slice_stroke_color: default_slice_stroke_color(),
+                archived: false,
+                shuffle_slices: false,
+                winner_cooldown: 0,
+                no_immediate_repeats: false,
+                weight_presets: Vec::new(),
+                center_label: None,
+                confirm_winner: false,
+                default_item_weight: 0,
+                restore_in_place: false,
+                seed: None,
+                locked: false,
+                weight_mode: false,
+                pick_count: 1,
+                spin_length: SpinLength::Medium,
+                pointer_pos: PointerPos::Top,
+                clockwise: false,
+                allow_duplicates: false,
             },
             state: WheelState { pct_bufs: empty_pct_bufs, ..Default::default() },
         }
@@ -108,8 +671,9 @@ impl Wheel {
         for _ in 0..number_of_items {
             empty_pct_bufs.push(String::new());
         }
+        let seed_input = wheel_data.seed.map(|seed| seed.to_string()).unwrap_or_default();
         Self {
-            state: WheelState { pct_bufs: empty_pct_bufs, ..Default::default() },
+            state: WheelState { pct_bufs: empty_pct_bufs, seed_input, ..Default::default() },
             data: wheel_data,
         }
     }
@@ -125,483 +689,4877 @@ impl Wheel {
         total
     }
 
-    fn sync_pct_bufs(&mut self) {
-        let number_of_items = self.data.items.len();
-        self.state.pct_bufs.resize(number_of_items, String::new());
+    // weight that gives new items an equal share, either rounded or exact by scaling every
+    // existing weight up first. zero weight items don't count, they aren't sharing anything
+    fn make_room_for_new_item(&mut self) -> u32 {
+        let number_of_items = self.data.items.iter().filter(|item| item.weight > 0).count() as u32;
+        if number_of_items == 0 {
+            return 1;
+        }
+        let total = self.total_weight();
+        if self.data.scale_on_add && !total.is_multiple_of(number_of_items) {
+            let scale = number_of_items / gcd(total, number_of_items);
+            if total.saturating_mul(scale) <= MAX_SCALED_TOTAL_WEIGHT {
+                for item in self.data.items.iter_mut() {
+                    item.weight *= scale;
+                }
+                return total * scale / number_of_items;
+            }
+        }
+        ((total + number_of_items / 2) / number_of_items).max(1)
     }
 
-    fn apply_pct_input(&mut self, item_index: usize) -> bool {
-        let raw_input = self.state.pct_bufs[item_index].trim().trim_end_matches('%').to_string();
-        let parsed = raw_input.parse::<f32>();
-        let pct = match parsed {
-            Ok(value) => value,
-            Err(_) => return false,
-        };
+    // the wheel's default weight if it has one, otherwise an equal share
+    fn weight_for_new_item(&mut self) -> u32 {
+        if self.data.default_item_weight > 0 { self.data.default_item_weight } else { self.make_room_for_new_item() }
+    }
 
-        let number_of_items = self.data.items.len() as f32;
-        let min_pct = 1.0_f32;
-        let max_pct = (100.0 - (number_of_items - 1.0)).max(1.0);
-        let clamped_pct = pct.clamp(min_pct, max_pct);
+    fn add_item(&mut self, name: String) {
+        let new_weight = self.weight_for_new_item();
+        self.add_item_with_weight(name, new_weight);
+    }
 
-        let mut others_total_weight = 0_u32;
-        for (index, item) in self.data.items.iter().enumerate() {
-            if index != item_index {
-                others_total_weight += item.weight;
-            }
-        }
-        if others_total_weight == 0 {
-            others_total_weight = 1;
+    fn add_item_with_weight(&mut self, name: String, weight: u32) {
+        let mut new_item = Item::new(name);
+        new_item.weight = weight;
+        self.data.items.push(new_item);
+        for buf in self.state.pct_bufs.iter_mut() {
+            buf.clear();
         }
+        self.state.pct_bufs.push(String::new());
+    }
 
-        let new_weight = ((clamped_pct / (100.0 - clamped_pct)) * others_total_weight as f32).round() as u32;
-        self.data.items[item_index].weight = new_weight.max(1);
-        true
+    // trimmed and case-insensitive, so "Gerbil " and "gerbil" count as the same name
+    fn has_item_named(&self, name: &str) -> bool {
+        let wanted_name = name.trim().to_lowercase();
+        self.data.items.iter().any(|item| item.name.trim().to_lowercase() == wanted_name)
     }
 
-    fn spin(&mut self) {
-        self.state.velocity = rand::thread_rng().gen_range(0.5..0.8);
-        self.state.rotation = 0.0;
-        self.state.is_spinning = true;
-        self.state.has_stopped = false;
-        self.state.stop_delay = 0.0;
-        self.state.editing_idx = None;
+    // what every add path checks, duplicates only get through when the wheel allows them
+    fn rejects_name(&self, name: &str) -> bool {
+        !self.data.allow_duplicates && self.has_item_named(name)
     }
 
-    fn tick(&mut self, dt: f32) -> bool {
-        if !self.state.is_spinning {
-            return false;
+    // already built items from an import or another wheel, same duplicate rule as a paste.
+    // gives back how many were added and how many were skipped as duplicates
+    fn add_whole_items(&mut self, new_items: Vec<Item>) -> (usize, usize) {
+        let mut added_count = 0;
+        let mut duplicate_count = 0;
+        for item in new_items {
+            if self.rejects_name(&item.name) {
+                duplicate_count += 1;
+                continue;
+            }
+            self.data.items.push(item);
+            added_count += 1;
         }
+        // the shares all moved, so every box gets refilled rather than just the new ones
+        self.state.pct_bufs.clear();
+        self.sync_pct_bufs();
+        (added_count, duplicate_count)
+    }
 
-        if !self.state.has_stopped {
-            self.state.rotation += self.state.velocity;
-            self.state.velocity *= 0.975;
-            if self.state.velocity < 0.001 {
-                self.state.has_stopped = true;
+    // one item per line, "name, 3" or "name (2d6)" set the weight, blank lines are skipped.
+    // gives back how many were added and how many were skipped as duplicates
+    fn add_items_bulk(&mut self, pasted_text: &str) -> (usize, usize) {
+        let mut added_count = 0;
+        let mut duplicate_count = 0;
+        // worked out once for the whole paste so the scaling doesn't compound line by line,
+        // every line without a weight gets the same share
+        let mut unweighted_item_weight = None;
+        for line in pasted_text.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
             }
-        } else {
-            self.state.stop_delay += dt;
-            if self.state.stop_delay >= 1.0 {
-                self.state.is_spinning = false;
-                if !self.data.items.is_empty() {
-                    let winning_index = self.get_winner();
-                    let winning_name = self.data.items[winning_index].name.clone();
-                    self.data.winner_history.insert(0, winning_name);
-                    if self.data.remove_winner {
-                        let removed_item = self.data.items.remove(winning_index);
-                        self.data.removed_items.push(removed_item);
-                        self.state.pct_bufs.remove(winning_index);
-                    }
-                    if self.data.auto_spin && self.data.remove_winner && self.data.items.len() > 1 {
-                        self.spin();
-                    }
-                    return true;
+            let comma_weight = line
+                .rsplit_once(',')
+                .and_then(|(name, weight)| Some((name.trim(), weight.trim().parse::<u32>().ok()?)))
+                .filter(|(name, weight)| !name.is_empty() && *weight > 0);
+            let notation_weight = split_weight_notation(line)
+                .and_then(|(name, notation)| Some((name, parse_weight_notation(notation)?)));
+            let parsed_weight = comma_weight.or(notation_weight);
+            let item_name = parsed_weight.map_or(line, |(item_name, _)| item_name);
+            // earlier lines of the same paste count too, they're on the wheel by now
+            if self.rejects_name(item_name) {
+                duplicate_count += 1;
+                continue;
+            }
+            match parsed_weight {
+                Some((item_name, weight)) => self.add_item_with_weight(item_name.to_string(), weight),
+                None => {
+                    let new_weight = *unweighted_item_weight.get_or_insert_with(|| self.weight_for_new_item());
+                    self.add_item_with_weight(line.to_string(), new_weight);
                 }
             }
+            added_count += 1;
         }
-
-        false
+        (added_count, duplicate_count)
     }
 
-    fn get_winner(&self) -> usize {
-        if self.data.items.is_empty() {
-            return 0;
-        }
-        let total_weight = self.total_weight() as f32;
-        let normalized_angle = ((-PI / 2.0 + self.state.rotation) % (2.0 * PI) + 2.0 * PI) % (2.0 * PI);
-        let fraction_of_circle = normalized_angle / (2.0 * PI);
-        let mut cumulative_fraction = 0.0_f32;
-        for (index, item) in self.data.items.iter().enumerate() {
-            cumulative_fraction += item.weight as f32 / total_weight;
-            if fraction_of_circle < cumulative_fraction {
-                return index;
+    // name,weight per line, names with commas or quotes get quoted
+    fn to_csv(&self) -> String {
+        let mut csv_text = String::from("name,weight\n");
+        for item in &self.data.items {
+            let needs_quotes = item.name.contains([',', '"', '\n']);
+            if needs_quotes {
+                csv_text.push_str(&format!("\"{}\",{}\n", item.name.replace('"', "\"\""), item.weight));
+            } else {
+                csv_text.push_str(&format!("{},{}\n", item.name, item.weight));
             }
         }
-        self.data.items.len() - 1
+        csv_text
     }
-}
 
-#[derive(serde::Serialize, serde::Deserialize)]
-struct SaveData {
-    wheels: Vec<WheelData>,
-    current: usize,
-}
+    // rows that don't have a name and a whole number weight are skipped and counted
+    fn from_csv(csv_text: &str) -> (Vec<Item>, usize) {
+        let mut parsed_items = Vec::new();
+        let mut skipped_rows = 0;
+        for (line_index, line) in csv_text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // the name is everything before the last comma, so unquoted commas in names still work
+            let Some((raw_name, raw_weight)) = line.rsplit_once(',') else {
+                skipped_rows += 1;
+                continue;
+            };
+            let raw_name = raw_name.trim();
+            let name = match raw_name.strip_prefix('"').and_then(|quoted| quoted.strip_suffix('"')) {
+                Some(quoted_name) => quoted_name.replace("\"\"", "\""),
+                None => raw_name.to_string(),
+            };
+            let is_header = line_index == 0 && name.eq_ignore_ascii_case("name") && raw_weight.trim().eq_ignore_ascii_case("weight");
+            if is_header {
+                continue;
+            }
+            match raw_weight.trim().parse::<u32>() {
+                Ok(weight) if !name.is_empty() => {
+                    let mut parsed_item = Item::new(name);
+                    parsed_item.weight = weight;
+                    parsed_items.push(parsed_item);
+                }
+                _ => skipped_rows += 1,
+            }
+        }
+        (parsed_items, skipped_rows)
+    }
 
-struct WheelApp {
-    wheels: Vec<Wheel>,
-    current: usize,
-    show_history: bool,
-    show_removed: bool,
-    last_time: std::time::Instant,
-    needs_save: bool,
-}
+    // appends a pasted [{"name": .., "weight": ..}] list, nothing changes if it doesn't parse.
+    // gives back (added, skipped as duplicates)
+    fn import_json(&mut self, json_text: &str) -> Result<(usize, usize), String> {
+        let imported_items: Vec<Item> = serde_json::from_str(json_text).map_err(|err| err.to_string())?;
+        Ok(self.add_whole_items(imported_items))
+    }
 
-// save / load data here
-impl WheelApp {
-    fn load() -> Self {
-        let save_file_path = Self::save_path();
-        if let Ok(file_contents) = fs::read_to_string(&save_file_path) {
-            if let Ok(save_data) = serde_json::from_str::<SaveData>(&file_contents) {
-                let current_wheel_index = save_data.current.min(save_data.wheels.len().saturating_sub(1));
-                let loaded_wheels: Vec<Wheel> = save_data.wheels.into_iter().map(Wheel::from_data).collect();
-                return Self {
-                    wheels: loaded_wheels,
-                    current: current_wheel_index,
-                    show_history: false,
-                    show_removed: false,
-                    last_time: std::time::Instant::now(),
-                    needs_save: false,
-                };
-            }
+    // gone for good, the removed list doesn't get it
+    fn delete_item(&mut self, item_index: usize) {
+        if item_index >= self.data.items.len() {
+            return;
         }
-        Self {
-            wheels: vec![Wheel::new("Wheel 1".to_string())],
-            current: 0,
-            show_history: false,
-            show_removed: false,
-            last_time: std::time::Instant::now(),
-            needs_save: false,
+        self.push_undo();
+        if self.state.editing_idx == Some(item_index) {
+            self.state.editing_idx = None;
+        }
+        self.data.items.remove(item_index);
+        self.state.pct_bufs.remove(item_index);
+        for buf in self.state.pct_bufs.iter_mut() {
+            buf.clear();
         }
     }
 
-    fn save_data(&self) {
-        let mut all_wheel_data = Vec::new();
-        for wheel in &self.wheels {
-            all_wheel_data.push(wheel.data.clone());
+    // returns false when there was nothing to clear, so the undo stash survives
+    fn clear_all(&mut self) -> bool {
+        if self.data.items.is_empty() && self.data.winner_history.is_empty() {
+            return false;
         }
-        let save_data = SaveData {
-            wheels: all_wheel_data,
-            current: self.current,
-        };
-        if let Ok(json_string) = serde_json::to_string_pretty(&save_data) {
-            let save_file_path = Self::save_path();
-            if let Some(parent_folder) = save_file_path.parent() {
-                let _ = fs::create_dir_all(parent_folder);
-            }
-            let _ = fs::write(save_file_path, json_string);
+        self.push_undo();
+        let cleared_items = std::mem::take(&mut self.data.items);
+        let cleared_history = std::mem::take(&mut self.data.winner_history);
+        self.state.pct_bufs.clear();
+        self.state.editing_idx = None;
+        self.state.cleared_stash = Some(ClearedStash {
+            items: cleared_items,
+            winner_history: cleared_history,
+        });
+        true
+    }
+
+    fn undo_clear(&mut self) {
+        if let Some(stash) = self.state.cleared_stash.take() {
+            self.data.items = stash.items;
+            self.data.winner_history = stash.winner_history;
+            self.state.pct_bufs.clear();
+            self.sync_pct_bufs();
         }
     }
 
-    fn save_path() -> PathBuf {
+    // steps back one spin at a time, so a whole elimination chain can be unwound
+    fn undo_spin(&mut self) -> Result<(), String> {
+        if self.state.is_spinning {
+            return Ok(());
+        }
+        let Some(spin_undo) = self.state.spin_undo.pop() else {
+            return Ok(());
+        };
+        // newest matching entry, if the history was cleared or trimmed since then the
+        // undo would put things back without taking the result away so refuse instead
+        let Some(history_index) = self
+            .data
+            .winner_history
+            .iter()
+            .position(|entry| entry.name == spin_undo.history_entry && entry.subset_of == spin_undo.history_subset)
+        else {
+            self.state.spin_undo.clear();
+            return Err("the history changed since that spin".to_string());
+        };
+        self.data.winner_history.remove(history_index);
+        if let Some(removed_at) = spin_undo.removed_at
+            && let Some(removed_index) = self.data.removed_items.iter().rposition(|item| item.name == spin_undo.winner_name)
+        {
+            let mut restored_item = self.data.removed_items.remove(removed_index);
+            restored_item.removed_from = None;
+            let insert_at = removed_at.min(self.data.items.len());
+            self.data.items.insert(insert_at, restored_item);
+            self.state.pct_bufs.insert(insert_at, String::new());
+            for buf in self.state.pct_bufs.iter_mut() {
+                buf.clear();
+            }
+        }
+        for undone_winner in self.data.items.iter_mut().filter(|_| spin_undo.counted_wins) {
+            if undone_winner.name == spin_undo.winner_name || spin_undo.other_picks.contains(&undone_winner.name) {
+                undone_winner.win_count = undone_winner.win_count.saturating_sub(1);
+            }
+        }
+        // otherwise the undone winner would still be sitting out, only safe if the list is as it was
+        if self.data.items.len() == spin_undo.cooldowns_before.len() {
+            for (item, &cooldown_before) in self.data.items.iter_mut().zip(&spin_undo.cooldowns_before) {
+                item.cooldown_left = cooldown_before;
+            }
+        }
+        Ok(())
+    }
+
+    // call before changing items, a new edit drops anything that could be redone
+    fn push_undo(&mut self) {
+        let snapshot = self.data.clone();
+        self.push_undo_snapshot(snapshot);
+    }
+
+    fn push_undo_snapshot(&mut self, snapshot: WheelData) {
+        self.state.undo_stack.push(snapshot);
+        if self.state.undo_stack.len() > MAX_EDIT_UNDO {
+            self.state.undo_stack.remove(0);
+        }
+        self.state.redo_stack.clear();
+    }
+
+    fn undo_edit(&mut self) -> bool {
+        let Some(snapshot) = self.state.undo_stack.pop() else {
+            return false;
+        };
+        let replaced = std::mem::replace(&mut self.data, snapshot);
+        self.state.redo_stack.push(replaced);
+        self.after_snapshot_restore();
+        true
+    }
+
+    fn redo_edit(&mut self) -> bool {
+        let Some(snapshot) = self.state.redo_stack.pop() else {
+            return false;
+        };
+        let replaced = std::mem::replace(&mut self.data, snapshot);
+        self.state.undo_stack.push(replaced);
+        self.after_snapshot_restore();
+        true
+    }
+
+    // indices into the item list might not line up any more
+    fn after_snapshot_restore(&mut self) {
+        self.state.editing_idx = None;
+        self.state.image_edit_idx = None;
+        self.state.notes_edit_idx = None;
+        self.state.slice_order.clear();
+        self.state.pct_bufs.clear();
+        self.sync_pct_bufs();
+        self.state.seed_input = self.data.seed.map(|seed| seed.to_string()).unwrap_or_default();
+        self.state.seed_sequence = None;
+    }
+
+    // temporary removal, remembers the spot so it can go back there
+    fn move_to_removed(&mut self, item_index: usize) {
+        let mut removed_item = self.data.items.remove(item_index);
+        removed_item.removed_from = Some(item_index);
+        self.data.removed_items.push(removed_item);
+        self.state.pct_bufs.remove(item_index);
+    }
+
+    // back where it was if that's turned on (clamped if the list shrank), otherwise on the end
+    fn reinsert_removed(&mut self, mut restored_item: Item) {
+        let original_index = restored_item.removed_from.take();
+        match original_index {
+            Some(original_index) if self.data.restore_in_place => {
+                let insert_at = original_index.min(self.data.items.len());
+                self.data.items.insert(insert_at, restored_item);
+            }
+            _ => self.data.items.push(restored_item),
+        }
+    }
+
+    fn restore_item(&mut self, removed_index: usize) {
+        if removed_index >= self.data.removed_items.len() {
+            return;
+        }
+        let restored_item = self.data.removed_items.remove(removed_index);
+        self.reinsert_removed(restored_item);
+        self.state.pct_bufs.clear();
+        self.sync_pct_bufs();
+    }
+
+    fn restore_all(&mut self) {
+        if self.data.restore_in_place {
+            // last removed goes back first, so earlier spots are right by the time they're used
+            while let Some(restored_item) = self.data.removed_items.pop() {
+                self.reinsert_removed(restored_item);
+            }
+        } else {
+            for restored_item in std::mem::take(&mut self.data.removed_items) {
+                self.reinsert_removed(restored_item);
+            }
+        }
+        self.state.pct_bufs.clear();
+        self.sync_pct_bufs();
+    }
+
+    fn can_spin(&self) -> bool {
+        !self.state.is_spinning && self.state.provisional_winner.is_none() && self.data.items.len() >= 2
+    }
+
+    // what each slice counts for this spin: boosts applied, and anything on cooldown
+    // (or the last winner, with no repeats on) left out as long as something else can still win
+    fn draw_weights(&self) -> Vec<u32> {
+        // if everything is at 0 there'd be no wheel at all, so treat them as equal
+        if self.data.items.iter().all(|item| item.effective_weight() == 0) {
+            return vec![1; self.data.items.len()];
+        }
+        let last_winner = self.data.winner_history.first().filter(|_| self.data.no_immediate_repeats).map(|entry| &entry.name);
+        let sits_out = |item: &Item| item.cooldown_left > 0 || Some(&item.name) == last_winner;
+        let anything_eligible = self.data.items.iter().any(|item| !sits_out(item) && item.effective_weight() > 0);
+        self.data
+            .items
+            .iter()
+            .map(|item| if anything_eligible && sits_out(item) { 0 } else { item.effective_weight() })
+            .collect()
+    }
+
+    // total of the draw weights, this is what the wheel and the pick use
+    fn total_effective_weight(&self) -> u32 {
+        let total: u32 = self.draw_weights().iter().sum();
+        total.max(1)
+    }
+
+    // how many items share the biggest slice, just for the odds note
+    fn top_weight_ties(&self) -> usize {
+        let max_weight = self.data.items.iter().map(|item| item.effective_weight()).max().unwrap_or(0);
+        // nothing can win at 0, so there's no top slice to share
+        if max_weight == 0 {
+            return 0;
+        }
+        self.data.items.iter().filter(|item| item.effective_weight() == max_weight).count()
+    }
+
+    // the colour a slice gets drawn in, also used for the recent winner chips. a loaded
+    // palette stands in for the generated rainbow
+    fn slice_color(&self, item_index: usize, slice_palette: Option<&SlicePalette>, color_scheme: ColorScheme) -> egui::Color32 {
+        if let Some([red, green, blue]) = self.data.items[item_index].color
+            && !self.data.heatmap_colors
+        {
+            return egui::Color32::from_rgb(red, green, blue);
+        }
+        if self.data.heatmap_colors {
+            let max_weight = self.data.items.iter().map(|item| item.effective_weight()).max().unwrap_or(1).max(1) as f32;
+            heatmap_color(self.data.items[item_index].effective_weight() as f32 / max_weight)
+        } else if let Some(slice_palette) = slice_palette {
+            let [red, green, blue] = slice_palette.colors[item_index % slice_palette.colors.len()];
+            egui::Color32::from_rgb(red, green, blue)
+        } else {
+            color_scheme.color(item_index, self.data.items.len())
+        }
+    }
+
+    fn has_boosts(&self) -> bool {
+        self.data.items.iter().any(|item| item.boost > 1)
+    }
+
+    // swaps an item with the one next to it, pct box and open editors follow it
+    fn swap_items(&mut self, first_index: usize, second_index: usize) {
+        if first_index.max(second_index) >= self.data.items.len() {
+            return;
+        }
+        self.sync_pct_bufs();
+        self.data.items.swap(first_index, second_index);
+        self.state.pct_bufs.swap(first_index, second_index);
+        let follow_swap = |index: &mut Option<usize>| {
+            if *index == Some(first_index) {
+                *index = Some(second_index);
+            } else if *index == Some(second_index) {
+                *index = Some(first_index);
+            }
+        };
+        follow_swap(&mut self.state.editing_idx);
+        follow_swap(&mut self.state.image_edit_idx);
+        follow_swap(&mut self.state.notes_edit_idx);
+    }
+
+    // item indices in the order the sort mode wants, manual is just list order
+    fn sorted_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.data.items.len()).collect();
+        let items = &self.data.items;
+        match self.data.sort_mode {
+            SortMode::Manual => {}
+            SortMode::NameAscending => order.sort_by_key(|&index| items[index].name.to_lowercase()),
+            SortMode::WeightDescending => order.sort_by(|&a, &b| items[b].weight.cmp(&items[a].weight)),
+        }
+        order
+    }
+
+    // actually reorders items (and their pct boxes) to match the sort, returns true if anything moved
+    fn apply_sort(&mut self) -> bool {
+        let order = self.sorted_order();
+        if order.iter().enumerate().all(|(position, &index)| position == index) {
+            return false;
+        }
+        self.sync_pct_bufs();
+        let mut old_items: Vec<Option<Item>> = std::mem::take(&mut self.data.items).into_iter().map(Some).collect();
+        let mut old_bufs: Vec<Option<String>> = std::mem::take(&mut self.state.pct_bufs).into_iter().map(Some).collect();
+        for &old_index in &order {
+            self.data.items.extend(old_items[old_index].take());
+            self.state.pct_bufs.extend(old_bufs[old_index].take());
+        }
+        if let Some(editing_index) = self.state.editing_idx {
+            self.state.editing_idx = order.iter().position(|&old_index| old_index == editing_index);
+        }
+        if let Some(image_edit_index) = self.state.image_edit_idx {
+            self.state.image_edit_idx = order.iter().position(|&old_index| old_index == image_edit_index);
+        }
+        if let Some(notes_edit_index) = self.state.notes_edit_idx {
+            self.state.notes_edit_idx = order.iter().position(|&old_index| old_index == notes_edit_index);
+        }
+        true
+    }
+
+    // saving under an existing name overwrites that preset
+    fn save_weight_preset(&mut self, preset_name: String) {
+        let weights: Vec<u32> = self.data.items.iter().map(|item| item.weight).collect();
+        match self.data.weight_presets.iter_mut().find(|(existing_name, _)| *existing_name == preset_name) {
+            Some((_, existing_weights)) => *existing_weights = weights,
+            None => self.data.weight_presets.push((preset_name, weights)),
+        }
+    }
+
+    // items added since the preset was saved just keep their weight, undo puts the old ones back
+    fn apply_weight_preset(&mut self, preset_name: &str) {
+        let Some((_, weights)) = self.data.weight_presets.iter().find(|(existing_name, _)| existing_name == preset_name) else {
+            return;
+        };
+        let weights = weights.clone();
+        self.push_undo();
+        for (item, &weight) in self.data.items.iter_mut().zip(&weights) {
+            item.weight = weight;
+        }
+        for buf in self.state.pct_bufs.iter_mut() {
+            buf.clear();
+        }
+    }
+
+    fn sync_pct_bufs(&mut self) {
+        let number_of_items = self.data.items.len();
+        self.state.pct_bufs.resize(number_of_items, String::new());
+    }
+
+    // the rounded percentages as the list shows them, added up
+    fn shown_pct_total(&self) -> u32 {
+        let total_weight = self.total_weight();
+        if total_weight == 0 {
+            return 0;
+        }
+        self.data.items.iter().map(|item| (item.weight as f32 / total_weight as f32 * 100.0).round() as u32).sum()
+    }
+
+    // weights become whole percentages adding up to 100, what rounding leaves over goes to
+    // the biggest slices. zero weight items stay at 0, returns false if nothing changed
+    fn balance_percentages(&mut self) -> bool {
+        let total_weight = self.total_weight();
+        let mut live_indices: Vec<usize> = (0..self.data.items.len()).filter(|&index| self.data.items[index].weight > 0).collect();
+        // every live slice needs at least 1%
+        if live_indices.is_empty() || live_indices.len() > 100 {
+            return false;
+        }
+        live_indices.sort_by(|&a, &b| self.data.items[b].weight.cmp(&self.data.items[a].weight));
+        let mut new_weights: Vec<u32> = live_indices
+            .iter()
+            .map(|&index| ((self.data.items[index].weight as u64 * 100 / total_weight as u64) as u32).max(1))
+            .collect();
+        let slice_count = new_weights.len();
+        let mut shown_total: u32 = new_weights.iter().sum();
+        let mut slice_turn = 0;
+        while shown_total < 100 {
+            new_weights[slice_turn % slice_count] += 1;
+            shown_total += 1;
+            slice_turn += 1;
+        }
+        // the 1% minimum can push it over, take it back off the big ones
+        slice_turn = 0;
+        while shown_total > 100 {
+            let position = slice_turn % slice_count;
+            if new_weights[position] > 1 {
+                new_weights[position] -= 1;
+                shown_total -= 1;
+            }
+            slice_turn += 1;
+        }
+        let mut anything_changed = false;
+        for (&index, &new_weight) in live_indices.iter().zip(&new_weights) {
+            anything_changed |= self.data.items[index].weight != new_weight;
+            self.data.items[index].weight = new_weight;
+        }
+        for buf in self.state.pct_bufs.iter_mut() {
+            buf.clear();
+        }
+        anything_changed
+    }
+
+    // what the number box shows when it's refilled, depends on the mode
+    fn weight_field_text(&self, item_index: usize, item_pct: f32) -> String {
+        if self.data.weight_mode {
+            self.data.items[item_index].weight.to_string()
+        } else {
+            format!("{:.0}", item_pct.round())
+        }
+    }
+
+    fn set_weight_mode(&mut self, weight_mode: bool) {
+        self.data.weight_mode = weight_mode;
+        // same boxes, different meaning, so they all get refilled
+        for buf in self.state.pct_bufs.iter_mut() {
+            buf.clear();
+        }
+    }
+
+    fn apply_weight_field(&mut self, item_index: usize) -> bool {
+        if !self.data.weight_mode {
+            return self.apply_pct_input(item_index);
+        }
+        match self.state.pct_bufs[item_index].trim().parse::<u32>() {
+            Ok(new_weight) => {
+                self.data.items[item_index].weight = new_weight.max(1);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn apply_pct_input(&mut self, item_index: usize) -> bool {
+        let pct = match parse_pct(&self.state.pct_bufs[item_index]) {
+            Some(value) => value,
+            None => return false,
+        };
+
+        // 0 keeps the item on the list but out of the draw
+        if pct <= 0.0 {
+            self.data.items[item_index].weight = 0;
+            return true;
+        }
+
+        let live_others = self
+            .data
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(index, item)| *index != item_index && item.weight > 0)
+            .count() as f32;
+        let min_pct = 1.0_f32;
+        let max_pct = (100.0 - live_others).max(1.0);
+        let clamped_pct = pct.clamp(min_pct, max_pct);
+
+        let mut others_total_weight = 0_u32;
+        for (index, item) in self.data.items.iter().enumerate() {
+            if index != item_index {
+                others_total_weight += item.weight;
+            }
+        }
+        if others_total_weight == 0 {
+            others_total_weight = 1;
+        }
+
+        let new_weight = ((clamped_pct / (100.0 - clamped_pct)) * others_total_weight as f32).round() as u32;
+        self.data.items[item_index].weight = new_weight.max(1);
+        true
+    }
+
+    // a fresh spin, decides up front whether an elimination chain is allowed to follow
+    fn spin(&mut self, spin_rng: &mut SpinRng) {
+        self.state.elimination_allowed = self.elimination_ready();
+        self.start_spin(spin_rng);
+    }
+
+    fn elimination_ready(&self) -> bool {
+        self.data.items.len() >= self.data.min_elimination_items as usize
+    }
+
+    // replays take their seeds from the archive, everything else from the spin rng
+    fn next_spin_seed(&mut self, spin_rng: &mut SpinRng) -> u64 {
+        if let Some(replay) = &mut self.state.replay
+            && let Some(archived_spin) = replay.upcoming.pop_front()
+        {
+            let seed = archived_spin.seed;
+            replay.current = Some(archived_spin);
+            return seed;
+        }
+        self.fresh_spin_seed(spin_rng)
+    }
+
+    // wheel seed steps through its own sequence, otherwise it's whatever the app rng gives
+    fn fresh_spin_seed(&mut self, spin_rng: &mut SpinRng) -> u64 {
+        let Some(wheel_seed) = self.data.seed else {
+            return spin_rng.next_seed();
+        };
+        let mut rng = StdRng::seed_from_u64(self.state.seed_sequence.unwrap_or(wheel_seed));
+        let seed = rng.r#gen();
+        self.state.seed_sequence = Some(rng.r#gen());
+        seed
+    }
+
+    // empty clears it, returns false if the text isn't a number
+    fn set_seed_from_input(&mut self) -> bool {
+        let seed_text = self.state.seed_input.trim();
+        let new_seed = if seed_text.is_empty() {
+            None
+        } else {
+            match seed_text.parse::<u64>() {
+                Ok(seed) => Some(seed),
+                Err(_) => return false,
+            }
+        };
+        self.data.seed = new_seed;
+        self.state.seed_sequence = None;
+        true
+    }
+
+    fn start_spin(&mut self, spin_rng: &mut SpinRng) {
+        let seed = self.next_spin_seed(spin_rng);
+        self.state.spin_seed = seed;
+        self.state.spin_was_instant = false;
+        let mut rng = StdRng::seed_from_u64(seed);
+        if self.data.shuffle_slices {
+            let mut shuffled_order: Vec<usize> = (0..self.data.items.len()).collect();
+            shuffled_order.shuffle(&mut rng);
+            self.state.slice_order = shuffled_order;
+        } else {
+            self.state.slice_order.clear();
+        }
+        self.state.velocity = rng.gen_range(self.data.spin_length.velocity_range());
+        self.state.landed_layout = None;
+        self.state.rotation = 0.0;
+        self.state.is_spinning = true;
+        self.state.has_stopped = false;
+        self.state.stop_delay = 0.0;
+        self.state.editing_idx = None;
+    }
+
+    fn start_next_queued_spin(&mut self, spin_rng: &mut SpinRng) {
+        self.state.queue_pause = None;
+        if self.data.items.len() < 2 {
+            self.cancel_queue();
+            return;
+        }
+        if let Some(next_label) = self.state.spin_queue.pop_front() {
+            self.state.active_queue_label = Some(next_label);
+            self.spin(spin_rng);
+        }
+    }
+
+    // throws away the spin that's going without a result, so a chain, queue or replay ends
+    // here too. a replay missing a spin wouldn't match its archive any more
+    fn stop_spinning(&mut self) {
+        self.state.is_spinning = false;
+        self.state.has_stopped = true;
+        self.state.velocity = 0.0;
+        self.state.stop_delay = 0.0;
+        self.state.replay = None;
+        self.cancel_queue();
+    }
+
+    fn cancel_queue(&mut self) {
+        self.state.spin_queue.clear();
+        self.state.active_queue_label = None;
+        self.state.queue_pause = None;
+    }
+
+    fn tick(&mut self, dt: f32, spin_rng: &mut SpinRng) -> bool {
+        if let Some(waited) = self.state.queue_pause
+            && !self.state.is_spinning
+        {
+            let waited = waited + dt;
+            if waited >= QUEUE_PAUSE_SECONDS {
+                self.start_next_queued_spin(spin_rng);
+            } else {
+                self.state.queue_pause = Some(waited);
+            }
+        }
+
+        if !self.state.is_spinning {
+            return false;
+        }
+
+        if !self.state.has_stopped {
+            // same distance either way, only mirrored, so every slice keeps the same odds
+            self.state.rotation += self.state.velocity * self.data.spin_direction();
+            self.state.velocity *= self.data.spin_length.decay();
+            if self.state.velocity < 0.001 {
+                self.state.has_stopped = true;
+            }
+        } else {
+            self.state.stop_delay += dt;
+            if self.state.stop_delay >= 1.0 {
+                self.state.is_spinning = false;
+                if !self.data.items.is_empty() {
+                    let winning_index = self.get_winner();
+                    let landed_layout = LandedLayout { item_signature: self.item_signature(), slice_weights: self.draw_weights() };
+                    if self.data.confirm_winner {
+                        let winning_name = self.data.items[winning_index].name.clone();
+                        self.state.provisional_winner = Some((winning_index, winning_name));
+                    } else {
+                        self.settle_spin(winning_index, spin_rng);
+                    }
+                    if !self.state.is_spinning {
+                        self.state.landed_layout = Some(landed_layout);
+                    }
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    // the result counts: bookkeeping, then carry on with any chain or queue
+    fn settle_spin(&mut self, winning_index: usize, spin_rng: &mut SpinRng) {
+        let picks = self.multi_pick(winning_index);
+        self.finish_picks(&picks);
+        // protected items never leave and zero weight ones can't win, so stop once only they're left.
+        // when every weight is 0 draw_weights makes them all equal, so then any of them can go
+        let every_weight_zero = self.data.items.iter().all(|item| item.weight == 0);
+        let any_removable_left = self.data.items.iter().any(|item| !item.protected && (item.weight > 0 || every_weight_zero));
+        // multi picks don't remove anything, so a chain would never end
+        let chain_continues = self.data.auto_spin
+            && picks.len() == 1
+            && self.data.remove_winner
+            && self.state.elimination_allowed
+            && self.data.items.len() > 1
+            && any_removable_left;
+        if chain_continues {
+            self.start_spin(spin_rng);
+        } else if !self.state.spin_queue.is_empty() {
+            self.state.queue_pause = Some(0.0);
+        }
+    }
+
+    // accepting settles it like a normal spin, rejecting throws it away and spins again
+    fn resolve_provisional(&mut self, accepted: bool, spin_rng: &mut SpinRng) {
+        let Some((winning_index, winning_name)) = self.state.provisional_winner.take() else {
+            return;
+        };
+        if !accepted {
+            self.start_spin(spin_rng);
+            return;
+        }
+        // the list might have been reordered while it waited
+        let winning_index = if self.data.items.get(winning_index).is_some_and(|item| item.name == winning_name) {
+            Some(winning_index)
+        } else {
+            self.data.items.iter().position(|item| item.name == winning_name)
+        };
+        if let Some(winning_index) = winning_index {
+            self.settle_spin(winning_index, spin_rng);
+        }
+    }
+
+    // drawing order for the slices, falls back to list order if the items changed
+    // since the last shuffle
+    fn slice_order(&self) -> Vec<usize> {
+        if self.state.slice_order.len() == self.data.items.len() {
+            self.state.slice_order.clone()
+        } else {
+            (0..self.data.items.len()).collect()
+        }
+    }
+
+    // runs the current spin (and any elimination chain or queue after it) to the end
+    // right away, ticking a whole second at a time
+    fn run_spin_to_end(&mut self, spin_rng: &mut SpinRng) -> bool {
+        let mut any_finished = false;
+        while self.state.is_spinning || self.state.queue_pause.is_some() {
+            any_finished |= self.tick(1.0, spin_rng);
+        }
+        any_finished
+    }
+
+    // history, queue results and winner removal, shared by animated and headless draws
+    fn finish_spin(&mut self, winning_index: usize) {
+        self.finish_picks(&[winning_index]);
+    }
+
+    // the landed slice plus however many more the wheel asks for, each drawn from
+    // what's left. seeded off the spin so a replay picks the same ones
+    fn multi_pick(&self, winning_index: usize) -> Vec<usize> {
+        let pick_count = (self.data.pick_count.max(1) as usize).min(self.data.items.len());
+        let mut picks = vec![winning_index];
+        let mut rng = StdRng::seed_from_u64(self.state.spin_seed.wrapping_add(1));
+        while picks.len() < pick_count {
+            let still_in: Vec<bool> = (0..self.data.items.len()).map(|index| !picks.contains(&index)).collect();
+            match self.pick_winner(&mut rng, Some(&still_in)) {
+                Some(picked_index) if !picks.contains(&picked_index) => picks.push(picked_index),
+                // only things that can't win are left
+                _ => break,
+            }
+        }
+        picks
+    }
+
+    // adds the spin to the event being recorded and scores it against the one being replayed
+    fn record_result(&mut self, winner: &str, subset: Vec<String>) {
+        let spin_was_certified = std::mem::take(&mut self.state.spin_was_certified);
+        if let Some(event_recording) = &mut self.state.event_recording {
+            event_recording.spins.push(ArchivedSpin {
+                seed: self.state.spin_seed,
+                winner: winner.to_string(),
+                timestamp: chrono::Utc::now().timestamp(),
+                instant: self.state.spin_was_instant,
+                subset,
+                certified: spin_was_certified,
+            });
+        }
+        if let Some(replay) = &mut self.state.replay
+            && let Some(archived_spin) = replay.current.take()
+        {
+            if archived_spin.winner == winner {
+                replay.matched += 1;
+            } else {
+                replay.mismatched += 1;
+            }
+        }
+    }
+
+    // picks[0] is the slice the wheel landed on, only a single winner ever gets removed
+    fn finish_picks(&mut self, picks: &[usize]) {
+        let winning_index = picks[0];
+        let picked_names: Vec<String> = picks.iter().map(|&index| self.data.items[index].name.clone()).collect();
+        let winning_name = picked_names.join(", ");
+        self.record_result(&winning_name, Vec::new());
+        if let Some(queue_label) = self.state.active_queue_label.take() {
+            self.state.queue_results.push((queue_label, winning_name.clone()));
+        }
+        self.data.winner_history.insert(0, HistoryEntry::now(winning_name.clone()));
+        let cooldowns_before: Vec<u32> = self.data.items.iter().map(|item| item.cooldown_left).collect();
+        for item in self.data.items.iter_mut() {
+            item.boost = 1;
+            item.cooldown_left = item.cooldown_left.saturating_sub(1);
+        }
+        for &picked_index in picks {
+            let picked_item = &mut self.data.items[picked_index];
+            picked_item.win_count += 1;
+            picked_item.cooldown_left = picked_item.cooldown.unwrap_or(self.data.winner_cooldown);
+        }
+        let winner_is_protected = self.data.items[winning_index].protected;
+        let winner_removed = self.data.remove_winner && !winner_is_protected && picks.len() == 1;
+        self.state.spin_undo.push(SpinUndo {
+            winner_name: picked_names[0].clone(),
+            history_entry: winning_name,
+            history_subset: None,
+            other_picks: picked_names[1..].to_vec(),
+            removed_at: winner_removed.then_some(winning_index),
+            cooldowns_before,
+            counted_wins: true,
+        });
+        if self.state.spin_undo.len() > MAX_SPIN_UNDO {
+            self.state.spin_undo.remove(0);
+        }
+        if winner_removed {
+            self.move_to_removed(winning_index);
+            // keep the shuffled layout for the rest of the wheel
+            self.state.slice_order.retain(|&item_index| item_index != winning_index);
+            for item_index in self.state.slice_order.iter_mut() {
+                if *item_index > winning_index {
+                    *item_index -= 1;
+                }
+            }
+        }
+    }
+
+    // instant pick for the decide button, works with a single item too
+    fn decide_now(&mut self, spin_rng: &mut SpinRng) -> Option<String> {
+        if self.state.is_spinning || self.state.provisional_winner.is_some() {
+            return None;
+        }
+        let seed = self.fresh_spin_seed(spin_rng);
+        let winning_index = self.pick_winner(&mut StdRng::seed_from_u64(seed), None)?;
+        self.state.spin_seed = seed;
+        self.state.spin_was_instant = true;
+        let picks = self.multi_pick(winning_index);
+        self.finish_picks(&picks);
+        self.data.winner_history.first().map(|entry| entry.name.clone())
+    }
+
+    fn start_event_recording(&mut self) {
+        self.state.event_recording = Some(EventArchive {
+            started_utc: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            initial_wheel: self.data.clone(),
+            spins: Vec::new(),
+        });
+    }
+
+    // a copy of the archived wheel set up to play the spins back in order
+    fn for_replay(event_archive: &EventArchive) -> Self {
+        let mut replay_wheel = Wheel::from_data(event_archive.initial_wheel.clone());
+        replay_wheel.data.name = format!("{} (replay)", event_archive.initial_wheel.name);
+        // a replay shouldn't stop and wait for anyone
+        replay_wheel.data.confirm_winner = false;
+        replay_wheel.state.replay = Some(EventReplay {
+            upcoming: event_archive.spins.iter().cloned().collect(),
+            ..Default::default()
+        });
+        replay_wheel
+    }
+
+    // starts the next archived spin once the wheel is idle and the pause is over,
+    // false when there's nothing left to play
+    fn advance_replay(&mut self, dt: f32, spin_rng: &mut SpinRng) -> bool {
+        let is_spinning = self.state.is_spinning;
+        let Some(replay) = &mut self.state.replay else {
+            return false;
+        };
+        if replay.upcoming.is_empty() {
+            return false;
+        }
+        if is_spinning {
+            return true;
+        }
+        replay.pause += dt;
+        if replay.pause < REPLAY_PAUSE_SECONDS {
+            return true;
+        }
+        replay.pause = 0.0;
+        let next_subset = replay.upcoming.front().map(|archived_spin| archived_spin.subset.clone()).unwrap_or_default();
+        if !next_subset.is_empty() {
+            let included: Vec<bool> = self.data.items.iter().map(|item| next_subset.contains(&item.name)).collect();
+            let seed = self.next_spin_seed(spin_rng);
+            if let Some(winning_index) = self.pick_from_selection(seed, &included) {
+                self.finish_selected_pick(seed, winning_index, &included);
+            }
+            return true;
+        }
+        let next_is_certified = replay.upcoming.front().is_some_and(|archived_spin| archived_spin.certified);
+        if next_is_certified {
+            let seed = self.next_spin_seed(spin_rng);
+            if let Some(winning_index) = self.certified_pick(seed) {
+                self.finish_certified_draw(seed, winning_index);
+            }
+            return true;
+        }
+        let next_is_instant = replay.upcoming.front().is_some_and(|archived_spin| archived_spin.instant);
+        if !next_is_instant {
+            self.start_spin(spin_rng);
+            return true;
+        }
+        let seed = self.next_spin_seed(spin_rng);
+        if let Some(winning_index) = self.pick_winner(&mut StdRng::seed_from_u64(seed), None) {
+            self.state.spin_seed = seed;
+            self.state.spin_was_instant = true;
+            let picks = self.multi_pick(winning_index);
+            self.finish_picks(&picks);
+        }
+        true
+    }
+
+    // weighted pick without the animation, walks the items in list order. `only` limits
+    // it to the marked items
+    fn pick_winner(&self, rng: &mut impl Rng, only: Option<&[bool]>) -> Option<usize> {
+        if self.data.items.is_empty() {
+            return None;
+        }
+        let mut draw_weights = self.draw_weights();
+        if let Some(included) = only {
+            for (weight, &is_included) in draw_weights.iter_mut().zip(included) {
+                if !is_included {
+                    *weight = 0;
+                }
+            }
+        }
+        let total_weight: u64 = draw_weights.iter().map(|&weight| weight as u64).sum();
+        if total_weight == 0 {
+            return Some(rng.gen_range(0..self.data.items.len()));
+        }
+        let mut roll = rng.gen_range(0..total_weight);
+        for (index, &weight) in draw_weights.iter().enumerate() {
+            if roll < weight as u64 {
+                return Some(index);
+            }
+            roll -= weight as u64;
+        }
+        Some(self.data.items.len() - 1)
+    }
+
+    // one off pick among the ticked items, the wheel is left alone apart from the history
+    fn spin_selected(&mut self, spin_rng: &mut SpinRng) -> Option<String> {
+        let included: Vec<bool> = self.data.items.iter().map(|item| item.selected).collect();
+        if !included.contains(&true) || self.state.is_spinning || self.state.provisional_winner.is_some() {
+            return None;
+        }
+        let seed = self.fresh_spin_seed(spin_rng);
+        let winning_index = self.pick_from_selection(seed, &included)?;
+        self.finish_selected_pick(seed, winning_index, &included);
+        Some(self.data.items[winning_index].name.clone())
+    }
+
+    fn pick_from_selection(&self, seed: u64, included: &[bool]) -> Option<usize> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let winning_index = self.pick_winner(&mut rng, Some(included))?;
+        if included[winning_index] {
+            return Some(winning_index);
+        }
+        // pick_winner goes uniform when every weight is 0, keep that inside the selection too
+        let selected_indices: Vec<usize> = (0..included.len()).filter(|&index| included[index]).collect();
+        selected_indices.get(rng.gen_range(0..selected_indices.len().max(1))).copied()
+    }
+
+    // history, undo and the event archive for a spin selected, nothing else on the wheel changes
+    fn finish_selected_pick(&mut self, seed: u64, winning_index: usize, included: &[bool]) {
+        let winning_name = self.data.items[winning_index].name.clone();
+        let subset: Vec<String> = self
+            .data
+            .items
+            .iter()
+            .zip(included)
+            .filter(|&(_, &is_included)| is_included)
+            .map(|(item, _)| item.name.clone())
+            .collect();
+        let subset_size = subset.len();
+        self.state.spin_seed = seed;
+        self.state.spin_was_instant = true;
+        self.record_result(&winning_name, subset);
+        let mut history_entry = HistoryEntry::now(winning_name.clone());
+        history_entry.subset_of = Some(subset_size);
+        self.data.winner_history.insert(0, history_entry);
+        self.state.spin_undo.push(SpinUndo {
+            winner_name: winning_name.clone(),
+            history_entry: winning_name,
+            history_subset: Some(subset_size),
+            other_picks: Vec::new(),
+            removed_at: None,
+            cooldowns_before: self.data.items.iter().map(|item| item.cooldown_left).collect(),
+            counted_wins: false,
+        });
+        if self.state.spin_undo.len() > MAX_SPIN_UNDO {
+            self.state.spin_undo.remove(0);
+        }
+    }
+
+    // picks the winner for a certified draw, the seed comes from the same place a normal
+    // spin's would so a wheel seed or sequence mode makes certificates repeatable too
+    fn draw_certificate(&mut self, spin_rng: &mut SpinRng) -> Option<DrawCertificate> {
+        let seed = self.fresh_spin_seed(spin_rng);
+        let winning_index = self.certified_pick(seed)?;
+        Some(DrawCertificate::new(&self.data, &self.draw_weights(), seed, winning_index))
+    }
+
+    // chacha20 instead of StdRng, StdRng's algorithm can change between rand versions
+    fn certified_pick(&self, seed: u64) -> Option<usize> {
+        self.pick_winner(&mut ChaCha20Rng::seed_from_u64(seed), None)
+    }
+
+    // single winner only, a certificate names exactly one. marked certified so a replay
+    // redoes it with the same generator
+    fn finish_certified_draw(&mut self, seed: u64, winning_index: usize) {
+        self.state.spin_seed = seed;
+        self.state.spin_was_instant = true;
+        self.state.spin_was_certified = true;
+        self.finish_spin(winning_index);
+    }
+
+    // seeded headless draw that writes a certificate anyone can re-check with the seed
+    fn certified_draw(&mut self, spin_rng: &mut SpinRng, test_mode: bool) -> Result<PathBuf, String> {
+        if self.state.is_spinning || self.state.provisional_winner.is_some() {
+            return Err("The last result is still waiting for Accept or Reject".to_string());
+        }
+        let certificate = self.draw_certificate(spin_rng).ok_or("The wheel has no items")?;
+        let certificate_json = serde_json::to_string_pretty(&certificate).map_err(|err| err.to_string())?;
+
+        let mut certificate_path = WheelApp::save_folder();
+        certificate_path.push("certificates");
+        certificate_path.push(format!("{}-{}.json", file_safe_name(&self.data.name), certificate.timestamp));
+        write_app_file(test_mode, &certificate_path, certificate_json.as_bytes())?;
+
+        self.finish_certified_draw(certificate.seed, certificate.winner_index);
+        Ok(certificate_path)
+    }
+
+    // plain numbered lines or name,time csv, untimed entries just get a blank time
+    fn history_export(&self, as_csv: bool, oldest_first: bool) -> String {
+        let mut ordered_history: Vec<&HistoryEntry> = self.data.winner_history.iter().collect();
+        if oldest_first {
+            ordered_history.reverse();
+        }
+        let mut export_text = if as_csv { String::from("name,time\n") } else { String::new() };
+        for (history_index, entry) in ordered_history.into_iter().enumerate() {
+            let entry_time = entry.local_time().unwrap_or_default();
+            if as_csv {
+                let entry_label = entry.label();
+                let needs_quotes = entry_label.contains([',', '"', '\n']);
+                if needs_quotes {
+                    export_text.push_str(&format!("\"{}\",{}\n", entry_label.replace('"', "\"\""), entry_time));
+                } else {
+                    export_text.push_str(&format!("{},{}\n", entry_label, entry_time));
+                }
+            } else if entry_time.is_empty() {
+                export_text.push_str(&format!("{}. {}\n", history_index + 1, entry.label()));
+            } else {
+                export_text.push_str(&format!("{}. {}  ({})\n", history_index + 1, entry.label(), entry_time));
+            }
+        }
+        export_text
+    }
+
+    // newest first, as a numbered list or a little table
+    fn history_markdown(&self, as_table: bool) -> String {
+        let mut markdown = String::new();
+        if as_table {
+            markdown.push_str("| # | Winner | Time |\n|---|---|---|\n");
+            for (history_index, entry) in self.data.winner_history.iter().enumerate() {
+                let entry_time = entry.local_time().unwrap_or_default();
+                markdown.push_str(&format!("| {} | {} | {} |\n", history_index + 1, entry.label().replace('|', "\\|"), entry_time));
+            }
+        } else {
+            for (history_index, entry) in self.data.winner_history.iter().enumerate() {
+                match entry.local_time() {
+                    Some(entry_time) => markdown.push_str(&format!("{}. **{}** ({})\n", history_index + 1, entry.label(), entry_time)),
+                    None => markdown.push_str(&format!("{}. **{}**\n", history_index + 1, entry.label())),
+                }
+            }
+        }
+        markdown
+    }
+
+    fn item_signature(&self) -> Vec<(String, u32)> {
+        self.data.items.iter().map(|item| (item.name.clone(), item.weight)).collect()
+    }
+
+    // what the wheel is drawn with, the landed layout until something changes
+    fn display_weights(&self) -> Vec<u32> {
+        if let Some(landed_layout) = &self.state.landed_layout
+            && !self.state.is_spinning
+            && landed_layout.item_signature == self.item_signature()
+        {
+            return landed_layout.slice_weights.clone();
+        }
+        self.draw_weights()
+    }
+
+    // (item, start, end) in radians clockwise from where the wheel's zero is, before rotation.
+    // the drawing and get_winner both go through this so they can't disagree about edges
+    fn slice_spans(&self, weights: &[u32]) -> Vec<(usize, f32, f32)> {
+        let total_weight = weights.iter().sum::<u32>().max(1) as f32;
+        let mut spans = Vec::new();
+        let mut current_angle = 0.0_f32;
+        for item_index in self.slice_order() {
+            // items on cooldown (or the last winner with no repeats) sit this spin out
+            if weights[item_index] == 0 {
+                continue;
+            }
+            let slice_angle = 2.0 * PI * (weights[item_index] as f32 / total_weight);
+            spans.push((item_index, current_angle, current_angle + slice_angle));
+            current_angle += slice_angle;
+        }
+        spans
+    }
+
+    // the slice under the arrow, whichever edge pointer_pos puts it on
+    fn get_winner(&self) -> usize {
+        if self.data.items.is_empty() {
+            return 0;
+        }
+        self.item_at_angle(self.data.pointer_pos.angle(), &self.slice_spans(&self.draw_weights()))
+    }
+
+    // the slice at a screen angle from the middle of the wheel. slices are drawn from
+    // -rotation going clockwise, so it's whatever span holds that angle + rotation.
+    // a clockwise wheel has just been counting rotation down, so nothing changes here
+    fn item_at_angle(&self, screen_angle: f32, spans: &[(usize, f32, f32)]) -> usize {
+        let span_angle = ((screen_angle + self.state.rotation) % (2.0 * PI) + 2.0 * PI) % (2.0 * PI);
+        for &(item_index, _, span_end) in spans {
+            if span_angle < span_end {
+                return item_index;
+            }
+        }
+        // rounding can leave a sliver at the end, that belongs to the last drawn slice
+        spans.last().map(|&(item_index, _, _)| item_index).unwrap_or(0)
+    }
+}
+
+#[derive(serde::Serialize)]
+struct CertificateItem {
+    name: String,
+    weight: u32,
+}
+
+// crate versions the draw depends on, gen_range's mapping is only stable within a rand version
+const CERTIFICATE_RNG: &str = "ChaCha20Rng from rand_chacha 0.3, gen_range from rand 0.8";
+
+// proof of draw, the seed plus the item list is enough to redo the pick
+#[derive(serde::Serialize)]
+struct DrawCertificate {
+    wheel: String,
+    timestamp: i64,
+    time_utc: String,
+    seed: u64,
+    rng: String,
+    method: String,
+    items: Vec<CertificateItem>,
+    items_sha256: String,
+    winner_index: usize,
+    winner: String,
+}
+
+impl DrawCertificate {
+    fn new(wheel_data: &WheelData, draw_weights: &[u32], seed: u64, winning_index: usize) -> Self {
+        let now = chrono::Utc::now();
+        let items: Vec<CertificateItem> = wheel_data
+            .items
+            .iter()
+            .zip(draw_weights)
+            .map(|(item, &weight)| CertificateItem { name: item.name.clone(), weight })
+            .collect();
+        Self {
+            wheel: wheel_data.name.clone(),
+            timestamp: now.timestamp(),
+            time_utc: now.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            seed,
+            rng: CERTIFICATE_RNG.to_string(),
+            method: "ChaCha20Rng::seed_from_u64(seed).gen_range(0..total_weight as u64), then walk the items in order \
+                     subtracting each weight until the roll lands inside one"
+                .to_string(),
+            items_sha256: hash_items(&items),
+            items,
+            winner_index: winning_index,
+            winner: wheel_data.items[winning_index].name.clone(),
+        }
+    }
+}
+
+// biggest side we keep for slice images, anything larger just wastes texture memory
+const MAX_IMAGE_SIZE: u32 = 512;
+
+// loads (once) and caches the texture for an item image, failures are cached too so
+// a bad path doesn't get retried every frame
+fn item_texture(
+    ctx: &egui::Context,
+    image_cache: &mut HashMap<String, Option<egui::TextureHandle>>,
+    status_message: &mut Option<String>,
+    image_path: &str,
+) -> Option<egui::TextureId> {
+    let cached = image_cache.entry(image_path.to_string()).or_insert_with(|| match image::open(image_path) {
+        Ok(loaded_image) => {
+            let resized = loaded_image.thumbnail(MAX_IMAGE_SIZE, MAX_IMAGE_SIZE).to_rgba8();
+            let image_size = [resized.width() as usize, resized.height() as usize];
+            let color_image = egui::ColorImage::from_rgba_unmultiplied(image_size, resized.as_raw());
+            Some(ctx.load_texture(image_path, color_image, egui::TextureOptions::LINEAR))
+        }
+        Err(err) => {
+            *status_message = Some(format!("Couldn't load image {}: {}", image_path, err));
+            None
+        }
+    });
+    cached.as_ref().map(|texture| texture.id())
+}
+
+// textured triangle fan over the slice. uvs are worked out in the slice's own frame
+// (rotated so it points right) so the picture turns with the wheel, and the image is
+// cropped to cover the wedge without stretching
+fn image_wedge_mesh(texture_id: egui::TextureId, slice_points: &[egui::Pos2], wheel_center: egui::Pos2, slice_mid_angle: f32) -> egui::Shape {
+    let (sin_angle, cos_angle) = (-slice_mid_angle).sin_cos();
+    let local_points: Vec<egui::Vec2> = slice_points
+        .iter()
+        .map(|point| {
+            let offset = *point - wheel_center;
+            egui::vec2(offset.x * cos_angle - offset.y * sin_angle, offset.x * sin_angle + offset.y * cos_angle)
+        })
+        .collect();
+    let mut local_bounds = egui::Rect::NOTHING;
+    for local_point in &local_points {
+        local_bounds.extend_with(local_point.to_pos2());
+    }
+    let cover_size = local_bounds.width().max(local_bounds.height()).max(1.0);
+    let cover_min = local_bounds.center() - egui::vec2(cover_size, cover_size) / 2.0;
+
+    let mut mesh = egui::Mesh::with_texture(texture_id);
+    for (point, local_point) in slice_points.iter().zip(&local_points) {
+        let uv = (local_point.to_pos2() - cover_min) / cover_size;
+        mesh.vertices.push(egui::epaint::Vertex {
+            pos: *point,
+            uv: egui::pos2(uv.x, uv.y),
+            color: egui::Color32::WHITE,
+        });
+    }
+    for fan_index in 1..slice_points.len().saturating_sub(1) {
+        mesh.add_triangle(0, fan_index as u32, fan_index as u32 + 1);
+    }
+    egui::Shape::mesh(mesh)
+}
+
+// every file the app writes (bar the save itself, which checks test mode on its own) is
+// made by one of these two, so test mode turns them all into an error instead of touching the disk
+fn create_app_file(test_mode: bool, path: &Path) -> Result<fs::File, String> {
+    if test_mode {
+        return Err("test mode doesn't write files".to_string());
+    }
+    if let Some(parent_folder) = path.parent() {
+        fs::create_dir_all(parent_folder).map_err(|err| err.to_string())?;
+    }
+    fs::File::create(path).map_err(|err| err.to_string())
+}
+
+fn write_app_file(test_mode: bool, path: &Path, contents: &[u8]) -> Result<(), String> {
+    create_app_file(test_mode, path)?.write_all(contents).map_err(|err| err.to_string())
+}
+
+// drops a file in the exports folder next to the save, named after the wheel
+fn write_export(test_mode: bool, wheel_name: &str, extension: &str, contents: &str) -> Result<PathBuf, String> {
+    let mut export_path = WheelApp::save_folder();
+    export_path.push("exports");
+    export_path.push(format!("{}-{}.{}", file_safe_name(wheel_name), chrono::Utc::now().timestamp(), extension));
+    write_app_file(test_mode, &export_path, contents.as_bytes())?;
+    Ok(export_path)
+}
+
+// wheel names can have anything in them, keep file names boring
+fn file_safe_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect()
+}
+
+// sha256 over "name<TAB>weight<NEWLINE>" per item, in list order
+fn hash_items(items: &[CertificateItem]) -> String {
+    let mut hasher = Sha256::new();
+    for item in items {
+        hasher.update(format!("{}\t{}\n", item.name, item.weight).as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SaveData {
+    wheels: Vec<WheelData>,
+    current: usize,
+    #[serde(default)]
+    settings: AppSettings,
+    #[serde(default)]
+    spin_rng: SpinRng,
+    #[serde(default)]
+    edit_lock: EditLock,
+    #[serde(default)]
+    slice_palette: Option<SlicePalette>,
+    #[serde(default)]
+    window: Option<WindowGeometry>,
+}
+
+// where the window was and how big it was, in points, so the next launch opens the same
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+struct WindowGeometry {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+// colours loaded from a file that slices cycle through
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct SlicePalette {
+    name: String,
+    colors: Vec<[u8; 3]>,
+}
+
+impl SlicePalette {
+    // hex colours split by whitespace or commas, "#" optional, "//" starts a comment
+    fn parse(name: String, file_contents: &str) -> Result<Self, String> {
+        let mut colors = Vec::new();
+        for (line_index, line) in file_contents.lines().enumerate() {
+            let without_comment = line.split("//").next().unwrap_or("");
+            for token in without_comment.split(|c: char| c.is_whitespace() || c == ',').filter(|token| !token.is_empty()) {
+                let hex = token.trim_start_matches('#');
+                let parsed = (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+                    .then(|| u32::from_str_radix(hex, 16).ok())
+                    .flatten();
+                match parsed {
+                    Some(rgb) => colors.push([(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8]),
+                    None => return Err(format!("line {}: \"{}\" isn't a hex colour like #ff8800", line_index + 1, token)),
+                }
+            }
+        }
+        if colors.is_empty() {
+            return Err("no colours in the file".to_string());
+        }
+        Ok(Self { name, colors })
+    }
+}
+
+// kiosk lock, people can still spin but nothing else. the pin is only a speed bump
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct EditLock {
+    locked: bool,
+    pin_hash: Option<String>,
+}
+
+fn hash_pin(pin: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"gerbil-decide-pin:");
+    hasher.update(pin.trim().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[derive(Default)]
+struct PinPrompt {
+    entered_pin: String,
+    wrong_pin: bool,
+}
+
+// edits that throw data away wait here until the confirm window gets a yes.
+// indices are into self.wheels and the current wheel's items when it was asked
+#[derive(Clone, PartialEq)]
+enum PendingAction {
+    ClearAll,
+    DeleteWheel(usize),
+    DeleteItem(usize),
+    ClearHistory,
+    ResetStats,
+    ClearAllHistory,
+    DeleteProfile(String),
+}
+
+// the profile that lives in the old wheels.json, so saves from before profiles still load
+const DEFAULT_PROFILE: &str = "Default";
+
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+enum RngMode {
+    #[default]
+    Entropy,
+    Sequence,
+}
+
+// where spin velocities come from. sequence mode walks a saved state forward every
+// spin so a whole session (even across restarts) replays the same way
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct SpinRng {
+    mode: RngMode,
+    base_seed: u64,
+    state: u64,
+}
+
+impl SpinRng {
+    // a seed per spin so it can be written down and replayed
+    fn next_seed(&mut self) -> u64 {
+        self.next_rng().r#gen()
+    }
+
+    fn next_rng(&mut self) -> StdRng {
+        match self.mode {
+            RngMode::Entropy => StdRng::from_entropy(),
+            RngMode::Sequence => {
+                let mut rng = StdRng::seed_from_u64(self.state);
+                self.state = rng.r#gen();
+                rng
+            }
+        }
+    }
+
+    fn restart_sequence(&mut self) {
+        self.state = self.base_seed;
+    }
+}
+
+// spin sounds need the system audio libs, so they only get built in with --features sound
+#[derive(Clone, Copy)]
+enum SoundClip {
+    Tick,
+    Chime,
+}
+
+#[cfg(feature = "sound")]
+struct SoundPlayer {
+    // dropping the stream cuts off all sound, it just has to stay alive
+    _output_stream: rodio::OutputStream,
+    output_handle: rodio::OutputStreamHandle,
+}
+
+#[cfg(feature = "sound")]
+impl SoundPlayer {
+    fn open() -> Option<Self> {
+        let (output_stream, output_handle) = rodio::OutputStream::try_default().ok()?;
+        Some(Self { _output_stream: output_stream, output_handle })
+    }
+
+    // speed above 1 plays it faster and higher
+    fn play(&self, sound_clip: SoundClip, speed: f32) {
+        use rodio::Source;
+        let clip_bytes: &'static [u8] = match sound_clip {
+            SoundClip::Tick => include_bytes!("../assets/tick.wav"),
+            SoundClip::Chime => include_bytes!("../assets/chime.wav"),
+        };
+        if let Ok(decoded_clip) = rodio::Decoder::new(std::io::Cursor::new(clip_bytes)) {
+            let _ = self.output_handle.play_raw(decoded_clip.convert_samples().speed(speed));
+        }
+    }
+}
+
+// stand in for builds without sound, the mute toggle just does nothing
+#[cfg(not(feature = "sound"))]
+struct SoundPlayer;
+
+#[cfg(not(feature = "sound"))]
+impl SoundPlayer {
+    fn open() -> Option<Self> {
+        None
+    }
+
+    fn play(&self, _sound_clip: SoundClip, _speed: f32) {}
+}
+
+// app wide settings, per wheel stuff lives on WheelData
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct AppSettings {
+    animations: bool,
+    stack_below_width: f32,
+    gif_fps: u32,
+    gif_max_size: u32,
+    show_removed_inline: bool,
+    image_slices: bool,
+    slice_percentages: bool,
+    color_scheme: ColorScheme,
+    touch_mode: bool,
+    recent_winner_chips: usize,
+    attract_mode: bool,
+    attract_idle_seconds: f32,
+    // quiet unless someone turns it on
+    muted: bool,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            animations: true,
+            stack_below_width: 640.0,
+            gif_fps: 15,
+            gif_max_size: 320,
+            show_removed_inline: false,
+            image_slices: false,
+            slice_percentages: false,
+            color_scheme: ColorScheme::Rainbow,
+            touch_mode: false,
+            recent_winner_chips: 5,
+            attract_mode: false,
+            attract_idle_seconds: 60.0,
+            muted: true,
+        }
+    }
+}
+
+// bigger buttons, spacing and text for fingers, or back to egui's normal sizes
+fn apply_touch_style(ctx: &egui::Context, touch_mode: bool) {
+    ctx.style_mut(|style| {
+        style.spacing = egui::style::Spacing::default();
+        style.text_styles = egui::style::default_text_styles();
+        if touch_mode {
+            style.spacing.item_spacing = egui::vec2(12.0, 10.0);
+            style.spacing.button_padding = egui::vec2(14.0, 8.0);
+            style.spacing.interact_size = egui::vec2(56.0, 40.0);
+            style.spacing.icon_width = 24.0;
+            style.spacing.icon_width_inner = 14.0;
+            style.spacing.slider_width = 180.0;
+            for font_id in style.text_styles.values_mut() {
+                font_id.size *= 1.3;
+            }
+        }
+    });
+}
+
+// frames grabbed from screenshots of the wheel while a recorded spin runs
+struct GifRecording {
+    wheel_name: String,
+    frames: Vec<GifFrame>,
+    since_last_capture: f32,
+    waiting_for_screenshot: bool,
+}
+
+struct GifFrame {
+    width: u16,
+    height: u16,
+    rgba: Vec<u8>,
+}
+
+enum GifProgress {
+    Encoded(usize, usize),
+    Finished(Result<PathBuf, String>),
+}
+
+// crops the wheel out of a full window screenshot and shrinks it to fit max_size
+fn crop_gif_frame(screenshot: &egui::ColorImage, wheel_rect: egui::Rect, pixels_per_point: f32, max_size: u32) -> Option<GifFrame> {
+    let [image_width, image_height] = screenshot.size;
+    let left = ((wheel_rect.left() * pixels_per_point).max(0.0) as usize).min(image_width);
+    let top = ((wheel_rect.top() * pixels_per_point).max(0.0) as usize).min(image_height);
+    let right = ((wheel_rect.right() * pixels_per_point) as usize).min(image_width);
+    let bottom = ((wheel_rect.bottom() * pixels_per_point) as usize).min(image_height);
+    if right <= left || bottom <= top {
+        return None;
+    }
+    let crop_width = right - left;
+    let crop_height = bottom - top;
+    let scale = (max_size as f32 / crop_width.max(crop_height) as f32).min(1.0);
+    let out_width = ((crop_width as f32 * scale) as usize).max(1);
+    let out_height = ((crop_height as f32 * scale) as usize).max(1);
+
+    let mut rgba = Vec::with_capacity(out_width * out_height * 4);
+    for out_y in 0..out_height {
+        let source_y = top + out_y * crop_height / out_height;
+        for out_x in 0..out_width {
+            let source_x = left + out_x * crop_width / out_width;
+            let pixel = screenshot.pixels[source_y * image_width + source_x];
+            rgba.extend_from_slice(&[pixel.r(), pixel.g(), pixel.b(), 255]);
+        }
+    }
+    Some(GifFrame { width: out_width as u16, height: out_height as u16, rgba })
+}
+
+fn encode_gif(
+    test_mode: bool,
+    frames: Vec<GifFrame>,
+    fps: u32,
+    path: PathBuf,
+    progress: &mpsc::Sender<GifProgress>,
+) -> Result<PathBuf, String> {
+    let first_frame = frames.first().ok_or("No frames were captured")?;
+    let (gif_width, gif_height) = (first_frame.width, first_frame.height);
+    let file = create_app_file(test_mode, &path)?;
+    let mut encoder = gif::Encoder::new(file, gif_width, gif_height, &[]).map_err(|err| err.to_string())?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(|err| err.to_string())?;
+    let frame_delay = (100 / fps.max(1)) as u16;
+    let frame_count = frames.len();
+    for (frame_index, mut frame) in frames.into_iter().enumerate() {
+        // the window can be resized mid spin, skip anything that doesn't match
+        if frame.width != gif_width || frame.height != gif_height {
+            continue;
+        }
+        let mut gif_frame = gif::Frame::from_rgba_speed(frame.width, frame.height, &mut frame.rgba, 10);
+        gif_frame.delay = frame_delay;
+        encoder.write_frame(&gif_frame).map_err(|err| err.to_string())?;
+        let _ = progress.send(GifProgress::Encoded(frame_index + 1, frame_count));
+    }
+    Ok(path)
+}
+
+// everything the ctrl+p palette can do, each one just calls an existing handler
+#[derive(Clone, Copy, PartialEq)]
+enum Command {
+    Spin,
+    StopSpin,
+    DecideNow,
+    RunQueue,
+    CancelQueue,
+    NewWheel,
+    DuplicateWheel,
+    NewWheelFromRemoved,
+    DeleteWheel,
+    NextWheel,
+    PreviousWheel,
+    ClearAll,
+    UndoClear,
+    UndoSpin,
+    UndoEdit,
+    RedoEdit,
+    RestoreAll,
+    ClearHistory,
+    ToggleHeatmap,
+    ToggleAnimations,
+}
+
+impl Command {
+    const ALL: [Command; 20] = [
+        Command::Spin,
+        Command::StopSpin,
+        Command::DecideNow,
+        Command::RunQueue,
+        Command::CancelQueue,
+        Command::NewWheel,
+        Command::DuplicateWheel,
+        Command::NewWheelFromRemoved,
+        Command::DeleteWheel,
+        Command::NextWheel,
+        Command::PreviousWheel,
+        Command::ClearAll,
+        Command::UndoClear,
+        Command::UndoSpin,
+        Command::UndoEdit,
+        Command::RedoEdit,
+        Command::RestoreAll,
+        Command::ClearHistory,
+        Command::ToggleHeatmap,
+        Command::ToggleAnimations,
+    ];
+
+    // everything except spinning and moving between wheels counts as editing for the lock
+    fn edits(self) -> bool {
+        !matches!(
+            self,
+            Command::Spin | Command::StopSpin | Command::DecideNow | Command::RunQueue | Command::CancelQueue | Command::NextWheel | Command::PreviousWheel
+        )
+    }
+
+    // the ones a locked wheel says no to, spinning and switching wheels still go through
+    fn edits_wheel(self) -> bool {
+        matches!(
+            self,
+            Command::DeleteWheel
+                | Command::ClearAll
+                | Command::UndoClear
+                | Command::UndoSpin
+                | Command::UndoEdit
+                | Command::RedoEdit
+                | Command::RestoreAll
+                | Command::ClearHistory
+                | Command::ToggleHeatmap
+        )
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Command::Spin => "Spin",
+            Command::StopSpin => "Stop spinning",
+            Command::DecideNow => "Decide now (no animation)",
+            Command::RunQueue => "Run spin queue",
+            Command::CancelQueue => "Cancel spin queue",
+            Command::NewWheel => "New wheel",
+            Command::DuplicateWheel => "Duplicate wheel",
+            Command::NewWheelFromRemoved => "New wheel from removed items",
+            Command::DeleteWheel => "Delete wheel",
+            Command::NextWheel => "Next wheel",
+            Command::PreviousWheel => "Previous wheel",
+            Command::ClearAll => "Clear all items",
+            Command::UndoClear => "Undo clear",
+            Command::UndoSpin => "Undo last spin",
+            Command::UndoEdit => "Undo edit (Ctrl+Z)",
+            Command::RedoEdit => "Redo edit (Ctrl+Y)",
+            Command::RestoreAll => "Restore all removed items",
+            Command::ClearHistory => "Clear winner history",
+            Command::ToggleHeatmap => "Toggle heatmap colors",
+            Command::ToggleAnimations => "Toggle animations",
+        }
+    }
+}
+
+#[derive(Default)]
+struct CommandPalette {
+    open: bool,
+    query: String,
+    selected: usize,
+    last_command: Option<Command>,
+}
+
+struct ModalKeys {
+    pressed_enter: bool,
+    pressed_escape: bool,
+}
+
+// enter confirms and escape cancels in every modal, consumed so the panels underneath
+// don't also act on them
+fn consume_modal_keys(ctx: &egui::Context) -> ModalKeys {
+    ctx.input_mut(|i| ModalKeys {
+        pressed_enter: i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+        pressed_escape: i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+    })
+}
+
+// radians per second the wheel drifts round in attract mode
+const ATTRACT_SPEED: f32 = 0.3;
+
+// anything the person at the screen did this frame, used to wake up from attract mode
+fn user_interacted(ctx: &egui::Context) -> bool {
+    ctx.input(|i| {
+        i.events.iter().any(|event| {
+            matches!(
+                event,
+                egui::Event::PointerMoved(_)
+                    | egui::Event::PointerButton { .. }
+                    | egui::Event::Key { .. }
+                    | egui::Event::Text(_)
+                    | egui::Event::MouseWheel { .. }
+                    | egui::Event::Touch { .. }
+                    | egui::Event::Paste(_)
+            )
+        })
+    })
+}
+
+// how many faded copies of the wheel the spin trail draws
+const TRAIL_COPIES: usize = 2;
+
+// how long the decide result stays up
+const DECIDE_FLASH_SECONDS: f32 = 1.5;
+
+// winner confetti, in points and seconds
+const CONFETTI_SECONDS: f32 = 1.5;
+const CONFETTI_COUNT: usize = 48;
+const CONFETTI_GRAVITY: f32 = 900.0;
+
+// one dot of the winner burst, offset is from the middle of the wheel
+struct ConfettiParticle {
+    offset: egui::Vec2,
+    velocity: egui::Vec2,
+    color: egui::Color32,
+}
+
+// how long the crossfade between wheels takes, half out and half in
+const WHEEL_SWITCH_SECONDS: f32 = 0.2;
+
+// changes are written once nothing else has changed for this long
+const SAVE_DELAY_SECONDS: f32 = 0.5;
+
+struct WheelApp {
+    wheels: Vec<Wheel>,
+    current: usize,
+    settings: AppSettings,
+    spin_rng: SpinRng,
+    edit_lock: EditLock,
+    pin_prompt: Option<PinPrompt>,
+    pending_action: Option<PendingAction>,
+    // which profile file the wheels come from and get saved to
+    profile: String,
+    new_profile_input: String,
+    // "don't ask again" in the confirm window, only lasts until the app closes
+    skip_confirmations: bool,
+    new_pin_input: String,
+    show_history: bool,
+    show_removed: bool,
+    show_settings: bool,
+    show_drawer: bool,
+    markdown_table: bool,
+    history_oldest_first: bool,
+    previous_wheel: Option<usize>,
+    switch_timer: f32,
+    palette: CommandPalette,
+    status_message: Option<String>,
+    wheel_rect: Option<egui::Rect>,
+    decide_flash: Option<(String, f32)>,
+    confetti: Vec<ConfettiParticle>,
+    confetti_age: f32,
+    sound_player: Option<SoundPlayer>,
+    // slice under the pointer last frame, a tick plays when it changes
+    last_pointer_item: Option<usize>,
+    window_geometry: Option<WindowGeometry>,
+    // the saved position still needs checking against the monitor once egui knows its size
+    window_check_pending: bool,
+    applied_touch_mode: Option<bool>,
+    image_cache: HashMap<String, Option<egui::TextureHandle>>,
+    gif_recording: Option<GifRecording>,
+    gif_progress: Option<mpsc::Receiver<GifProgress>>,
+    last_time: std::time::Instant,
+    needs_save: bool,
+    // when the last unsaved change happened, None means everything is on disk
+    last_change: Option<std::time::Instant>,
+    test_mode: bool,
+    idle_seconds: f32,
+    event_import_path: String,
+    slice_palette: Option<SlicePalette>,
+    palette_path_input: String,
+    // which wheel attract mode is turning and where it was before, put back on wake
+    attract_saved_rotation: Option<(usize, f32)>,
+}
+
+// save / load data here
+impl WheelApp {
+    fn load() -> Self {
+        Self::load_profile(Self::active_profile())
+    }
+
+    fn load_profile(profile: String) -> Self {
+        let save_file_path = Self::save_path(&profile);
+        let mut loaded_wheels = Vec::new();
+        let mut current_wheel_index = 0;
+        let mut settings = AppSettings::default();
+        let mut spin_rng = SpinRng::default();
+        let mut edit_lock = EditLock::default();
+        let mut slice_palette = None;
+        let mut window_geometry = None;
+        if let Ok(file_contents) = fs::read_to_string(&save_file_path)
+            && let Ok(save_data) = serde_json::from_str::<SaveData>(&file_contents)
+        {
+            current_wheel_index = save_data.current.min(save_data.wheels.len().saturating_sub(1));
+            loaded_wheels = save_data.wheels.into_iter().map(Wheel::from_data).collect();
+            settings = save_data.settings;
+            spin_rng = save_data.spin_rng;
+            edit_lock = save_data.edit_lock;
+            slice_palette = save_data.slice_palette.filter(|loaded_palette| !loaded_palette.colors.is_empty());
+            window_geometry = save_data.window;
+        }
+        if loaded_wheels.is_empty() {
+            loaded_wheels.push(Wheel::new("Wheel 1".to_string()));
+        }
+        let mut app = Self::from_parts(loaded_wheels, current_wheel_index, settings, spin_rng, edit_lock);
+        app.slice_palette = slice_palette;
+        app.window_check_pending = window_geometry.is_some();
+        app.window_geometry = window_geometry;
+        app.profile = profile;
+        app
+    }
+
+    // read before the window opens, main needs it for the viewport builder
+    fn saved_window_geometry() -> Option<WindowGeometry> {
+        let file_contents = fs::read_to_string(Self::save_path(&Self::active_profile())).ok()?;
+        serde_json::from_str::<SaveData>(&file_contents).ok()?.window
+    }
+
+    // keeps window_geometry in step with the real window, and once at startup moves a
+    // window that came back somewhere it can't be seen (like an unplugged monitor)
+    fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let (inner_rect, outer_rect, monitor_size, minimized, maximized, fullscreen) = ctx.input(|input| {
+            let viewport = input.viewport();
+            (
+                viewport.inner_rect,
+                viewport.outer_rect,
+                viewport.monitor_size,
+                viewport.minimized.unwrap_or(false),
+                viewport.maximized.unwrap_or(false),
+                viewport.fullscreen.unwrap_or(false),
+            )
+        });
+        if self.window_check_pending {
+            let Some(monitor_size) = monitor_size else {
+                return;
+            };
+            self.window_check_pending = false;
+            if let Some(saved) = self.window_geometry {
+                // egui only knows the size of the monitor the window is on, not where it
+                // sits, so this treats it as starting at 0,0. the top strip has to be on it
+                // so the title bar can still be grabbed
+                let monitor_rect = egui::Rect::from_min_size(egui::Pos2::ZERO, monitor_size);
+                let title_strip = egui::Rect::from_min_size(egui::pos2(saved.x, saved.y), egui::vec2(saved.width, 40.0));
+                if !monitor_rect.intersects(title_strip) {
+                    let centered = egui::pos2(
+                        ((monitor_size.x - saved.width) / 2.0).max(0.0),
+                        ((monitor_size.y - saved.height) / 2.0).max(0.0),
+                    );
+                    ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(centered));
+                    self.window_geometry = Some(WindowGeometry { x: centered.x, y: centered.y, ..saved });
+                }
+            }
+            return;
+        }
+        // a minimised or maximised window would come back wrong, keep the last normal size
+        if minimized || maximized || fullscreen {
+            return;
+        }
+        if let (Some(inner_rect), Some(outer_rect)) = (inner_rect, outer_rect) {
+            self.window_geometry = Some(WindowGeometry {
+                x: outer_rect.min.x,
+                y: outer_rect.min.y,
+                width: inner_rect.width(),
+                height: inner_rect.height(),
+            });
+        }
+    }
+
+    // fixed seed, nothing written to disk and spins that land the same frame they start, so the
+    // draw and removal logic can be driven without waiting on the animation
+    fn new_for_test(wheels: Vec<Wheel>, seed: u64) -> Self {
+        let settings = AppSettings { animations: false, ..AppSettings::default() };
+        let spin_rng = SpinRng { mode: RngMode::Sequence, base_seed: seed, state: seed };
+        let mut app = Self::from_parts(wheels, 0, settings, spin_rng, EditLock::default());
+        app.test_mode = true;
+        app
+    }
+
+    fn from_parts(wheels: Vec<Wheel>, current: usize, settings: AppSettings, spin_rng: SpinRng, edit_lock: EditLock) -> Self {
+        let sound_player = if settings.muted { None } else { SoundPlayer::open() };
+        Self {
+            wheels,
+            current,
+            settings,
+            spin_rng,
+            edit_lock,
+            pin_prompt: None,
+            pending_action: None,
+            profile: DEFAULT_PROFILE.to_string(),
+            new_profile_input: String::new(),
+            skip_confirmations: false,
+            new_pin_input: String::new(),
+            show_history: false,
+            show_removed: false,
+            show_settings: false,
+            show_drawer: true,
+            markdown_table: false,
+            history_oldest_first: false,
+            previous_wheel: None,
+            switch_timer: 0.0,
+            palette: CommandPalette::default(),
+            status_message: None,
+            wheel_rect: None,
+            decide_flash: None,
+            confetti: Vec::new(),
+            confetti_age: 0.0,
+            sound_player,
+            last_pointer_item: None,
+            window_geometry: None,
+            window_check_pending: false,
+            applied_touch_mode: None,
+            image_cache: HashMap::new(),
+            gif_recording: None,
+            gif_progress: None,
+            last_time: std::time::Instant::now(),
+            needs_save: false,
+            last_change: None,
+            test_mode: false,
+            idle_seconds: 0.0,
+            event_import_path: String::new(),
+            slice_palette: None,
+            palette_path_input: String::new(),
+            attract_saved_rotation: None,
+        }
+    }
+
+    fn to_save_data(&self) -> SaveData {
+        let mut all_wheel_data = Vec::new();
+        for wheel in &self.wheels {
+            all_wheel_data.push(wheel.data.clone());
+        }
+        SaveData {
+            wheels: all_wheel_data,
+            current: self.current,
+            settings: self.settings.clone(),
+            spin_rng: self.spin_rng.clone(),
+            edit_lock: self.edit_lock.clone(),
+            slice_palette: self.slice_palette.clone(),
+            window: self.window_geometry,
+        }
+    }
+
+    fn save_data(&self) {
+        if self.test_mode {
+            return;
+        }
+        if let Ok(json_string) = serde_json::to_string_pretty(&self.to_save_data()) {
+            let save_file_path = Self::save_path(&self.profile);
+            if let Some(parent_folder) = save_file_path.parent() {
+                let _ = fs::create_dir_all(parent_folder);
+            }
+            let _ = fs::write(save_file_path, json_string);
+        }
+    }
+
+    // the same thing save_data writes, just somewhere the user picked
+    fn export_all(&mut self) {
+        let Some(export_path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name("gerbil-decide-backup.json")
+            .save_file()
+        else {
+            return;
+        };
+        let result = serde_json::to_string_pretty(&self.to_save_data())
+            .map_err(|err| err.to_string())
+            .and_then(|json_string| write_app_file(self.test_mode, &export_path, json_string.as_bytes()));
+        self.status_message = Some(match result {
+            Ok(()) => format!("All wheels saved to {}", export_path.display()),
+            Err(err) => format!("Couldn't save {}: {}", export_path.display(), err),
+        });
+    }
+
+    // replaces every wheel with the ones in the file. unlike load() a bad file is reported
+    // rather than ignored, and nothing changes unless the whole thing reads
+    fn import_all(&mut self) {
+        let Some(import_path) = rfd::FileDialog::new().add_filter("JSON", &["json"]).pick_file() else {
+            return;
+        };
+        let parsed = fs::read_to_string(&import_path)
+            .map_err(|err| err.to_string())
+            .and_then(|file_contents| serde_json::from_str::<SaveData>(&file_contents).map_err(|err| err.to_string()));
+        let save_data = match parsed {
+            Ok(save_data) if save_data.wheels.is_empty() => {
+                self.status_message = Some(format!("{} doesn't have any wheels in it", import_path.display()));
+                return;
+            }
+            Ok(save_data) => save_data,
+            Err(err) => {
+                self.status_message = Some(format!("Couldn't import {}: {}", import_path.display(), err));
+                return;
+            }
+        };
+        self.current = save_data.current.min(save_data.wheels.len() - 1);
+        self.wheels = save_data.wheels.into_iter().map(Wheel::from_data).collect();
+        for wheel in self.wheels.iter_mut() {
+            wheel.sync_pct_bufs();
+        }
+        self.previous_wheel = None;
+        self.needs_save = true;
+        self.status_message = Some(format!("Imported {} wheels from {}", self.wheels.len(), import_path.display()));
+    }
+
+    fn add_new_wheel(&mut self) {
+        let new_wheel_name = format!("Wheel {}", self.wheels.len() + 1);
+        self.wheels.push(Wheel::new(new_wheel_name));
+        self.switch_to_wheel(self.wheels.len() - 1);
+        self.needs_save = true;
+    }
+
+    // same items and settings, but nothing that's happened on the original comes along
+    fn duplicate_current_wheel(&mut self) {
+        let mut copied_data = self.wheels[self.current].data.clone();
+        copied_data.name = format!("Copy of {}", copied_data.name);
+        copied_data.winner_history.clear();
+        copied_data.removed_items.clear();
+        copied_data.archived = false;
+        for item in copied_data.items.iter_mut() {
+            item.win_count = 0;
+            item.cooldown_left = 0;
+        }
+        self.wheels.push(Wheel::from_data(copied_data));
+        self.switch_to_wheel(self.wheels.len() - 1);
+        self.needs_save = true;
+    }
+
+    // copies of the other wheel's items go on the end of this one, that wheel isn't touched.
+    // gives back how many were added and how many were skipped as duplicates
+    fn merge_from_wheel(&mut self, source_index: usize) -> (usize, usize) {
+        if source_index == self.current || source_index >= self.wheels.len() {
+            return (0, 0);
+        }
+        let source_items = self.wheels[source_index].data.items.clone();
+        let current_wheel = &mut self.wheels[self.current];
+        current_wheel.push_undo();
+        let fresh_items: Vec<Item> = source_items
+            .into_iter()
+            .map(|mut item| {
+                item.win_count = 0;
+                item.cooldown_left = 0;
+                item
+            })
+            .collect();
+        let merge_counts = current_wheel.add_whole_items(fresh_items);
+        self.needs_save = true;
+        merge_counts
+    }
+
+    // weights come along as they were, the new wheel starts with no history
+    fn new_wheel_from_removed(&mut self, take_items: bool) {
+        let source_wheel = &mut self.wheels[self.current];
+        if source_wheel.data.removed_items.is_empty() {
+            return;
+        }
+        let new_items = if take_items {
+            std::mem::take(&mut source_wheel.data.removed_items)
+        } else {
+            source_wheel.data.removed_items.clone()
+        };
+        let mut new_wheel_data = Wheel::new(format!("{} (removed)", source_wheel.data.name)).data;
+        new_wheel_data.items.clear();
+        let mut new_wheel = Wheel::from_data(new_wheel_data);
+        let fresh_items: Vec<Item> = new_items
+            .into_iter()
+            .map(|mut item| {
+                item.removed_from = None;
+                item
+            })
+            .collect();
+        let (_, duplicate_count) = new_wheel.add_whole_items(fresh_items);
+        if duplicate_count > 0 {
+            self.status_message = Some(format!("Left out {} duplicate names from the new wheel", duplicate_count));
+        }
+        self.wheels.push(new_wheel);
+        self.switch_to_wheel(self.wheels.len() - 1);
+        self.needs_save = true;
+    }
+
+    fn delete_current_wheel(&mut self) {
+        if self.wheels.len() <= 1 {
+            return;
+        }
+        self.wheels.remove(self.current);
+        self.previous_wheel = None;
+        if self.current >= self.wheels.len() {
+            self.current = self.wheels.len() - 1;
+        }
+        self.needs_save = true;
+    }
+
+    fn run_command(&mut self, command: Command) {
+        if self.edit_lock.locked && command.edits() {
+            self.status_message = Some("Editing is locked".to_string());
+            return;
+        }
+        if self.wheels[self.current].data.locked && command.edits_wheel() {
+            self.status_message = Some("This wheel is locked".to_string());
+            return;
+        }
+        let wheel_count = self.wheels.len();
+        let current_wheel = &mut self.wheels[self.current];
+        // same as something_changed in the panel, any other edit makes an undo clear stale.
+        // the ones that ask first drop it in perform_action once they go ahead
+        let asks_first = matches!(command, Command::ClearAll | Command::ClearHistory | Command::DeleteWheel);
+        if command.edits_wheel() && !asks_first && command != Command::UndoClear {
+            current_wheel.state.cleared_stash = None;
+        }
+        match command {
+            Command::Spin => {
+                if current_wheel.can_spin() {
+                    current_wheel.spin(&mut self.spin_rng);
+                }
+            }
+            Command::StopSpin => current_wheel.stop_spinning(),
+            Command::DecideNow => {
+                self.decide_current_wheel();
+                return;
+            }
+            Command::RunQueue => {
+                let queue_idle = current_wheel.state.active_queue_label.is_none() && current_wheel.state.queue_pause.is_none();
+                if current_wheel.can_spin() && queue_idle {
+                    current_wheel.start_next_queued_spin(&mut self.spin_rng);
+                }
+            }
+            Command::CancelQueue => current_wheel.cancel_queue(),
+            Command::NewWheel => self.add_new_wheel(),
+            Command::DuplicateWheel => self.duplicate_current_wheel(),
+            Command::NewWheelFromRemoved => self.new_wheel_from_removed(false),
+            Command::DeleteWheel => {
+                if wheel_count > 1 {
+                    self.request_action(PendingAction::DeleteWheel(self.current));
+                }
+            }
+            Command::NextWheel => self.step_wheel(1),
+            Command::PreviousWheel => self.step_wheel(wheel_count - 1),
+            Command::ClearAll => {
+                if !current_wheel.data.items.is_empty() || !current_wheel.data.winner_history.is_empty() {
+                    self.request_action(PendingAction::ClearAll);
+                }
+            }
+            Command::UndoClear => {
+                current_wheel.undo_clear();
+                self.needs_save = true;
+            }
+            Command::UndoSpin => {
+                if let Err(err) = current_wheel.undo_spin() {
+                    self.status_message = Some(format!("Couldn't undo the spin: {}", err));
+                }
+                self.needs_save = true;
+            }
+            Command::UndoEdit => {
+                if current_wheel.undo_edit() {
+                    self.needs_save = true;
+                }
+            }
+            Command::RedoEdit => {
+                if current_wheel.redo_edit() {
+                    self.needs_save = true;
+                }
+            }
+            Command::RestoreAll => {
+                current_wheel.restore_all();
+                self.needs_save = true;
+            }
+            Command::ClearHistory => {
+                if !current_wheel.data.winner_history.is_empty() {
+                    self.request_action(PendingAction::ClearHistory);
+                }
+            }
+            Command::ToggleHeatmap => {
+                current_wheel.data.heatmap_colors = !current_wheel.data.heatmap_colors;
+                self.needs_save = true;
+            }
+            Command::ToggleAnimations => {
+                self.settings.animations = !self.settings.animations;
+                self.needs_save = true;
+            }
+        }
+        self.palette.last_command = Some(command);
+    }
+
+    // moves through the tabs the way they're shown, so archived wheels get skipped
+    fn step_wheel(&mut self, step: usize) {
+        let wheel_count = self.wheels.len();
+        let mut wheel_index = self.current;
+        for _ in 0..wheel_count {
+            wheel_index = (wheel_index + step) % wheel_count;
+            if !self.wheels[wheel_index].data.archived {
+                self.switch_to_wheel(wheel_index);
+                return;
+            }
+        }
+    }
+
+    // archiving the open wheel moves you to a visible one, the last visible wheel can't go
+    fn set_archived(&mut self, wheel_index: usize, archived: bool) {
+        if archived {
+            let visible_elsewhere = self.wheels.iter().enumerate().any(|(other_index, wheel)| other_index != wheel_index && !wheel.data.archived);
+            if !visible_elsewhere {
+                self.status_message = Some("Can't archive the only visible wheel".to_string());
+                return;
+            }
+        }
+        self.wheels[wheel_index].data.archived = archived;
+        if archived && wheel_index == self.current {
+            self.step_wheel(1);
+        }
+        self.needs_save = true;
+    }
+
+    // idle drift for kiosks, not a real spin so nothing is picked or recorded
+    fn update_attract_mode(&mut self, ctx: &egui::Context, dt: f32) {
+        let current_state = &self.wheels[self.current].state;
+        let busy = current_state.is_spinning || current_state.queue_pause.is_some() || self.modal_open();
+        if !self.settings.attract_mode || busy || user_interacted(ctx) {
+            self.idle_seconds = 0.0;
+            if let Some((wheel_index, saved_rotation)) = self.attract_saved_rotation.take()
+                && let Some(wheel) = self.wheels.get_mut(wheel_index)
+            {
+                wheel.state.rotation = saved_rotation;
+            }
+            if self.settings.attract_mode {
+                ctx.request_repaint_after(std::time::Duration::from_secs_f32(self.settings.attract_idle_seconds));
+            }
+            return;
+        }
+
+        self.idle_seconds += dt;
+        if self.idle_seconds < self.settings.attract_idle_seconds {
+            // nothing else repaints while idle, so wake up when the wait is over
+            let seconds_left = self.settings.attract_idle_seconds - self.idle_seconds;
+            ctx.request_repaint_after(std::time::Duration::from_secs_f32(seconds_left));
+            return;
+        }
+        let current_wheel = &mut self.wheels[self.current];
+        if self.attract_saved_rotation.is_none() {
+            self.attract_saved_rotation = Some((self.current, current_wheel.state.rotation));
+        }
+        current_wheel.state.rotation += ATTRACT_SPEED * dt * current_wheel.data.spin_direction();
+        ctx.request_repaint();
+    }
+
+    // checks the archive replays to the same winners, then opens it as a new wheel
+    // that plays the spins back
+    fn import_event_archive(&mut self) {
+        let event_archive = match fs::read_to_string(self.event_import_path.trim())
+            .map_err(|err| err.to_string())
+            .and_then(|file_contents| serde_json::from_str::<EventArchive>(&file_contents).map_err(|err| err.to_string()))
+        {
+            Ok(event_archive) => event_archive,
+            Err(err) => {
+                self.status_message = Some(format!("Couldn't load event archive: {}", err));
+                return;
+            }
+        };
+        let (matched, mismatched) = verify_event_archive(&event_archive);
+        self.status_message = Some(if mismatched == 0 {
+            format!("Event archive checks out, all {} winners replay the same", matched)
+        } else {
+            format!("Event archive doesn't replay cleanly: {} of {} winners differ", mismatched, matched + mismatched)
+        });
+        self.wheels.push(Wheel::for_replay(&event_archive));
+        self.switch_to_wheel(self.wheels.len() - 1);
+        self.needs_save = true;
+    }
+
+    fn load_slice_palette(&mut self) {
+        let palette_path = PathBuf::from(self.palette_path_input.trim());
+        let palette_name = palette_path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_else(|| "palette".to_string());
+        let loaded = fs::read_to_string(&palette_path)
+            .map_err(|err| err.to_string())
+            .and_then(|file_contents| SlicePalette::parse(palette_name, &file_contents));
+        match loaded {
+            Ok(slice_palette) => {
+                self.status_message = Some(format!("Loaded palette {} ({} colours)", slice_palette.name, slice_palette.colors.len()));
+                self.slice_palette = Some(slice_palette);
+                self.needs_save = true;
+            }
+            Err(err) => self.status_message = Some(format!("Couldn't load palette: {}", err)),
+        }
+    }
+
+    fn decide_current_wheel(&mut self) {
+        let current_wheel = &mut self.wheels[self.current];
+        if let Some(winning_name) = current_wheel.decide_now(&mut self.spin_rng) {
+            self.decide_flash = Some((winning_name, 0.0));
+            self.needs_save = true;
+        }
+        self.palette.last_command = Some(Command::DecideNow);
+    }
+
+    // one frame of the current wheel's spin, test mode runs it all the way out at once.
+    // true when a spin landed
+    fn advance_current_wheel(&mut self, dt: f32) -> bool {
+        let current_wheel = &mut self.wheels[self.current];
+        let spin_just_finished = if self.test_mode {
+            current_wheel.run_spin_to_end(&mut self.spin_rng)
+        } else {
+            current_wheel.tick(dt, &mut self.spin_rng)
+        };
+        if spin_just_finished {
+            self.needs_save = true;
+            self.maybe_spawn_confetti();
+        }
+        spin_just_finished
+    }
+
+    // only once the wheel has really settled, a chain or a waiting confirm isn't the end yet
+    fn maybe_spawn_confetti(&mut self) {
+        let current_state = &self.wheels[self.current].state;
+        if !self.settings.animations || current_state.is_spinning || current_state.provisional_winner.is_some() {
+            return;
+        }
+        // thread rng on purpose, the seeded one is kept for the spins
+        let mut rng = rand::thread_rng();
+        self.confetti.clear();
+        for _ in 0..CONFETTI_COUNT {
+            let launch_angle = rng.gen_range(-PI..0.0);
+            let launch_speed = rng.gen_range(250.0..650.0);
+            self.confetti.push(ConfettiParticle {
+                offset: egui::Vec2::ZERO,
+                velocity: launch_speed * egui::vec2(launch_angle.cos(), launch_angle.sin()),
+                color: egui::Color32::from_rgb(rng.r#gen(), rng.r#gen(), rng.r#gen()),
+            });
+        }
+        self.confetti_age = 0.0;
+    }
+
+    fn set_muted(&mut self, muted: bool) {
+        self.settings.muted = muted;
+        if !muted && self.sound_player.is_none() {
+            self.sound_player = SoundPlayer::open();
+            if self.sound_player.is_none() {
+                self.status_message = Some(if cfg!(feature = "sound") {
+                    "Couldn't open an audio device".to_string()
+                } else {
+                    "This build has no sound, rebuild with --features sound".to_string()
+                });
+            }
+        }
+        self.needs_save = true;
+    }
+
+    // a tick each time a new slice passes the pointer, so they slow down with the wheel
+    fn update_spin_sounds(&mut self, spin_just_finished: bool) {
+        let Some(sound_player) = self.sound_player.as_ref().filter(|_| !self.settings.muted) else {
+            self.last_pointer_item = None;
+            return;
+        };
+        let current_wheel = &self.wheels[self.current];
+        if spin_just_finished {
+            sound_player.play(SoundClip::Chime, 1.0);
+        }
+        if !current_wheel.state.is_spinning || current_wheel.state.has_stopped || current_wheel.data.items.is_empty() {
+            self.last_pointer_item = None;
+            return;
+        }
+        let pointer_item = current_wheel.get_winner();
+        if self.last_pointer_item.is_some_and(|last_item| last_item != pointer_item) {
+            let tick_speed = 0.8 + current_wheel.state.velocity.min(1.0) * 0.6;
+            sound_player.play(SoundClip::Tick, tick_speed);
+        }
+        self.last_pointer_item = Some(pointer_item);
+    }
+
+    fn update_confetti(&mut self, ctx: &egui::Context, dt: f32) {
+        if self.confetti.is_empty() {
+            return;
+        }
+        self.confetti_age += dt;
+        if self.confetti_age >= CONFETTI_SECONDS {
+            self.confetti.clear();
+            return;
+        }
+        for particle in self.confetti.iter_mut() {
+            particle.velocity.y += CONFETTI_GRAVITY * dt;
+            particle.offset += particle.velocity * dt;
+        }
+        ctx.request_repaint();
+    }
+
+    // big fading result in the middle of the screen after a decide
+    fn show_decide_flash(&mut self, ctx: &egui::Context, dt: f32) {
+        let Some((winning_name, shown_for)) = &mut self.decide_flash else {
+            return;
+        };
+        *shown_for += dt;
+        if *shown_for >= DECIDE_FLASH_SECONDS {
+            self.decide_flash = None;
+            return;
+        }
+        // hold for most of it then fade out
+        let fade_start = DECIDE_FLASH_SECONDS * 0.7;
+        let flash_opacity = if *shown_for < fade_start { 1.0 } else { 1.0 - (*shown_for - fade_start) / (DECIDE_FLASH_SECONDS - fade_start) };
+        egui::Area::new(egui::Id::new("decide_flash"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                ui.set_opacity(flash_opacity);
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.label("⚡ Decided:");
+                        ui.label(
+                            egui::RichText::new(winning_name.as_str())
+                                .size(48.0)
+                                .color(egui::Color32::from_rgb(255, 215, 0)),
+                        );
+                    });
+                });
+            });
+        ctx.request_repaint();
+    }
+
+    fn show_command_palette(&mut self, ctx: &egui::Context) {
+        if !self.palette.open {
+            return;
+        }
+        let query = self.palette.query.to_lowercase();
+        let mut matching_commands: Vec<Command> = Command::ALL
+            .iter()
+            .copied()
+            .filter(|command| command.label().to_lowercase().contains(&query))
+            .collect();
+        // last used one goes first so repeating it is just ctrl+p, enter
+        if let Some(last_command) = self.palette.last_command
+            && let Some(position) = matching_commands.iter().position(|command| *command == last_command)
+        {
+            let last = matching_commands.remove(position);
+            matching_commands.insert(0, last);
+        }
+        if matching_commands.is_empty() {
+            self.palette.selected = 0;
+        } else {
+            self.palette.selected = self.palette.selected.min(matching_commands.len() - 1);
+        }
+
+        let (pressed_up, pressed_down) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            )
+        });
+        let ModalKeys { pressed_enter, pressed_escape } = consume_modal_keys(ctx);
+        if pressed_escape {
+            self.palette.open = false;
+            return;
+        }
+        if pressed_up {
+            self.palette.selected = self.palette.selected.saturating_sub(1);
+        }
+        if pressed_down && self.palette.selected + 1 < matching_commands.len() {
+            self.palette.selected += 1;
+        }
+
+        let mut chosen_command = None;
+        if pressed_enter {
+            chosen_command = matching_commands.get(self.palette.selected).copied();
+        }
+
+        egui::Window::new("Command Palette")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+            .show(ctx, |ui| {
+                let query_response = ui.add(
+                    egui::TextEdit::singleline(&mut self.palette.query)
+                        .hint_text("type a command...")
+                        .desired_width(280.0)
+                );
+                query_response.request_focus();
+                if query_response.changed() {
+                    self.palette.selected = 0;
+                }
+                ui.separator();
+                for (command_index, command) in matching_commands.iter().enumerate() {
+                    let is_selected = command_index == self.palette.selected;
+                    let mut command_label = command.label().to_string();
+                    if Some(*command) == self.palette.last_command {
+                        command_label.push_str("  (last used)");
+                    }
+                    if ui.selectable_label(is_selected, command_label).clicked() {
+                        chosen_command = Some(*command);
+                    }
+                }
+                if matching_commands.is_empty() {
+                    ui.label("No matching commands");
+                }
+            });
+
+        if let Some(command) = chosen_command {
+            self.palette.open = false;
+            self.run_command(command);
+        }
+    }
+
+    fn start_gif_recording(&mut self) {
+        if self.test_mode {
+            self.status_message = Some("Couldn't record a GIF: test mode doesn't write files".to_string());
+            return;
+        }
+        let current_wheel = &mut self.wheels[self.current];
+        if !current_wheel.can_spin() || self.gif_progress.is_some() {
+            return;
+        }
+        current_wheel.spin(&mut self.spin_rng);
+        self.gif_recording = Some(GifRecording {
+            wheel_name: current_wheel.data.name.clone(),
+            frames: Vec::new(),
+            since_last_capture: f32::MAX,
+            waiting_for_screenshot: false,
+        });
+        self.status_message = Some("Recording spin...".to_string());
+    }
+
+    // grabs a screenshot every 1/fps while recording, then hands the frames to a worker thread
+    fn update_gif_recording(&mut self, ctx: &egui::Context, dt: f32) {
+        if let Some(receiver) = &self.gif_progress {
+            let mut finished = false;
+            while let Ok(progress) = receiver.try_recv() {
+                match progress {
+                    GifProgress::Encoded(done, total) => {
+                        self.status_message = Some(format!("Encoding GIF {}/{}...", done, total));
+                    }
+                    GifProgress::Finished(result) => {
+                        self.status_message = Some(match result {
+                            Ok(gif_path) => format!("GIF saved to {}", gif_path.display()),
+                            Err(err) => format!("Couldn't save GIF: {}", err),
+                        });
+                        finished = true;
+                    }
+                }
+            }
+            if finished {
+                self.gif_progress = None;
+            } else {
+                ctx.request_repaint();
+            }
+        }
+
+        let Some(recording) = &mut self.gif_recording else {
+            return;
+        };
+        let screenshot = ctx.input(|i| {
+            i.raw.events.iter().find_map(|event| match event {
+                egui::Event::Screenshot { image, .. } => Some(image.clone()),
+                _ => None,
+            })
+        });
+        if let Some(screenshot) = screenshot {
+            recording.waiting_for_screenshot = false;
+            if let Some(wheel_rect) = self.wheel_rect
+                && let Some(frame) = crop_gif_frame(&screenshot, wheel_rect, ctx.pixels_per_point(), self.settings.gif_max_size)
+            {
+                recording.frames.push(frame);
+            }
+        }
+
+        if self.wheels[self.current].state.is_spinning {
+            recording.since_last_capture += dt;
+            let capture_interval = 1.0 / self.settings.gif_fps.max(1) as f32;
+            if recording.since_last_capture >= capture_interval && !recording.waiting_for_screenshot {
+                recording.since_last_capture = 0.0;
+                recording.waiting_for_screenshot = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot);
+            }
+            return;
+        }
+
+        let Some(recording) = self.gif_recording.take() else {
+            return;
+        };
+        let mut gif_path = Self::save_folder();
+        gif_path.push("gifs");
+        gif_path.push(format!("{}-{}.gif", file_safe_name(&recording.wheel_name), chrono::Utc::now().timestamp()));
+        let gif_fps = self.settings.gif_fps;
+        let test_mode = self.test_mode;
+        let (sender, receiver) = mpsc::channel();
+        self.gif_progress = Some(receiver);
+        self.status_message = Some(format!("Encoding GIF 0/{}...", recording.frames.len()));
+        std::thread::spawn(move || {
+            let result = encode_gif(test_mode, recording.frames, gif_fps, gif_path, &sender);
+            let _ = sender.send(GifProgress::Finished(result));
+        });
+    }
+
+    // add any new dialogs here so the background gets locked while they're up
+    fn modal_open(&self) -> bool {
+        self.palette.open || self.pin_prompt.is_some() || self.pending_action.is_some()
+    }
+
+    fn clear_all_history(&mut self) {
+        for wheel in self.wheels.iter_mut() {
+            wheel.data.winner_history.clear();
+        }
+        self.needs_save = true;
+    }
+
+    // runs straight away once "don't ask again" is ticked, otherwise opens the confirm window
+    fn request_action(&mut self, action: PendingAction) {
+        if self.skip_confirmations {
+            self.perform_action(action);
+        } else {
+            self.pending_action = Some(action);
+        }
+    }
+
+    fn perform_action(&mut self, action: PendingAction) {
+        // undo clear only puts things back until something else changes the wheel
+        match action {
+            PendingAction::DeleteItem(_) | PendingAction::ClearHistory | PendingAction::ResetStats => {
+                self.wheels[self.current].state.cleared_stash = None;
+            }
+            PendingAction::ClearAllHistory => {
+                for wheel in self.wheels.iter_mut() {
+                    wheel.state.cleared_stash = None;
+                }
+            }
+            PendingAction::ClearAll | PendingAction::DeleteWheel(_) | PendingAction::DeleteProfile(_) => {}
+        }
+        match action {
+            PendingAction::ClearAll => {
+                self.wheels[self.current].clear_all();
+            }
+            PendingAction::DeleteWheel(wheel_index) => {
+                if wheel_index == self.current {
+                    self.delete_current_wheel();
+                }
+            }
+            PendingAction::DeleteItem(item_index) => {
+                self.wheels[self.current].delete_item(item_index);
+            }
+            PendingAction::ClearHistory => {
+                self.wheels[self.current].data.winner_history.clear();
+            }
+            PendingAction::ResetStats => {
+                for item in self.wheels[self.current].data.items.iter_mut() {
+                    item.win_count = 0;
+                }
+            }
+            PendingAction::ClearAllHistory => {
+                self.clear_all_history();
+                self.status_message = Some("Cleared the history on every wheel".to_string());
+            }
+            PendingAction::DeleteProfile(profile_name) => {
+                self.status_message = Some(match self.delete_profile(&profile_name) {
+                    Ok(()) => format!("Deleted profile {}", profile_name),
+                    Err(err) => err,
+                });
+            }
+        }
+        self.needs_save = true;
+    }
+
+    fn pending_action_message(&self, action: &PendingAction) -> String {
+        let current_wheel = &self.wheels[self.current];
+        match action {
+            PendingAction::ClearAll => format!("Clear every item and the history from {}?", current_wheel.data.name),
+            PendingAction::DeleteWheel(wheel_index) => {
+                let wheel_name = self.wheels.get(*wheel_index).map_or("this wheel", |wheel| wheel.data.name.as_str());
+                format!("Delete {} and everything on it?", wheel_name)
+            }
+            PendingAction::DeleteItem(item_index) => {
+                let item_name = current_wheel.data.items.get(*item_index).map_or("this item", |item| item.name.as_str());
+                format!("Delete {} for good? It won't go to the removed list.", item_name)
+            }
+            PendingAction::ClearHistory => {
+                format!("Clear all {} history entries from {}?", current_wheel.data.winner_history.len(), current_wheel.data.name)
+            }
+            PendingAction::ResetStats => format!("Set every win count on {} back to 0?", current_wheel.data.name),
+            PendingAction::ClearAllHistory => {
+                let history_count: usize = self.wheels.iter().map(|wheel| wheel.data.winner_history.len()).sum();
+                format!("Clear {} history entries across {} wheels? This can't be undone.", history_count, self.wheels.len())
+            }
+            PendingAction::DeleteProfile(profile_name) => format!("Delete the {} profile and all of its wheels?", profile_name),
+        }
+    }
+
+    fn show_confirm_prompt(&mut self, ctx: &egui::Context) {
+        let Some(action) = self.pending_action.clone() else {
+            return;
+        };
+        let ModalKeys { pressed_enter, pressed_escape } = consume_modal_keys(ctx);
+        let mut confirmed = pressed_enter;
+        let mut cancel = pressed_escape;
+        let message = self.pending_action_message(&action);
+        egui::Window::new("Are you sure?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(message);
+                ui.checkbox(&mut self.skip_confirmations, "Don't ask again this session");
+                ui.horizontal(|ui| {
+                    if ui.button("Yes").clicked() {
+                        confirmed = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+        if cancel {
+            self.pending_action = None;
+            // ticking the box and then backing out shouldn't stop the next one asking
+            self.skip_confirmations = false;
+        } else if confirmed {
+            self.pending_action = None;
+            self.perform_action(action);
+        }
+    }
+
+    fn show_pin_prompt(&mut self, ctx: &egui::Context) {
+        let Some(pin_prompt) = &mut self.pin_prompt else {
+            return;
+        };
+        let ModalKeys { pressed_enter, pressed_escape } = consume_modal_keys(ctx);
+        let mut try_unlock = pressed_enter;
+        let mut cancel = pressed_escape;
+        egui::Window::new("Unlock Editing")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("Enter the PIN to unlock editing:");
+                let pin_response = ui.add(egui::TextEdit::singleline(&mut pin_prompt.entered_pin).password(true));
+                if !pin_response.has_focus() && ui.memory(|m| m.focused().is_none()) {
+                    pin_response.request_focus();
+                }
+                if pin_prompt.wrong_pin {
+                    ui.colored_label(egui::Color32::from_rgb(230, 80, 60), "Wrong PIN");
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Unlock").clicked() {
+                        try_unlock = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+        if cancel {
+            self.pin_prompt = None;
+        } else if try_unlock {
+            if Some(hash_pin(&pin_prompt.entered_pin)) == self.edit_lock.pin_hash {
+                self.edit_lock.locked = false;
+                self.pin_prompt = None;
+                self.needs_save = true;
+            } else {
+                pin_prompt.wrong_pin = true;
+                pin_prompt.entered_pin.clear();
+            }
+        }
+    }
+
+    fn switch_to_wheel(&mut self, wheel_index: usize) {
+        if wheel_index == self.current {
+            return;
+        }
+        if self.settings.animations {
+            self.previous_wheel = Some(self.current);
+            self.switch_timer = 0.0;
+        }
+        self.current = wheel_index;
+    }
+
+    fn save_folder() -> PathBuf {
         let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
         path.push("wheel-picker");
-        path.push("wheels.json");
         path
     }
-}
 
-// eframe lol, this is where all of the actual UI is
-impl eframe::App for WheelApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        let current_time = std::time::Instant::now();
-        let dt = current_time.duration_since(self.last_time).as_secs_f32();
-        self.last_time = current_time;
+    fn save_path(profile: &str) -> PathBuf {
+        let mut path = Self::save_folder();
+        if profile == DEFAULT_PROFILE {
+            path.push("wheels.json");
+        } else {
+            path.push("profiles");
+            path.push(format!("{}.json", profile));
+        }
+        path
+    }
+
+    // the profile open last time, kept outside the profile files so it's known before loading
+    fn active_profile_path() -> PathBuf {
+        let mut path = Self::save_folder();
+        path.push("profile.txt");
+        path
+    }
+
+    fn active_profile() -> String {
+        fs::read_to_string(Self::active_profile_path())
+            .ok()
+            .map(|profile| profile.trim().to_string())
+            .filter(|profile| !profile.is_empty() && *profile == file_safe_name(profile))
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    }
+
+    fn set_active_profile(test_mode: bool, profile: &str) {
+        let _ = write_app_file(test_mode, &Self::active_profile_path(), profile.as_bytes());
+    }
+
+    // Default first, then whatever is in the profiles folder alphabetically
+    fn profile_names() -> Vec<String> {
+        let mut profiles_folder = Self::save_folder();
+        profiles_folder.push("profiles");
+        let mut profile_names: Vec<String> = fs::read_dir(profiles_folder)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .filter(|profile| profile != DEFAULT_PROFILE)
+            .collect();
+        profile_names.sort_by_key(|profile| profile.to_lowercase());
+        profile_names.insert(0, DEFAULT_PROFILE.to_string());
+        profile_names
+    }
+
+    // swaps the whole app over to another profile's wheels and settings, the current
+    // profile is saved first. the window and this session's choices stay as they are
+    fn replace_with_profile(&mut self, mut next_app: WheelApp) {
+        self.save_data();
+        Self::set_active_profile(self.test_mode, &next_app.profile);
+        next_app.window_geometry = self.window_geometry;
+        next_app.skip_confirmations = self.skip_confirmations;
+        *self = next_app;
+    }
+
+    fn switch_profile(&mut self, profile: String) {
+        if profile == self.profile {
+            return;
+        }
+        let next_app = Self::load_profile(profile);
+        self.replace_with_profile(next_app);
+        self.status_message = Some(format!("Switched to profile {}", self.profile));
+    }
+
+    // names end up as file names, so anything that isn't a letter or number becomes _
+    fn create_profile(&mut self, typed_name: &str) -> Result<String, String> {
+        let profile = file_safe_name(typed_name.trim());
+        if profile.is_empty() {
+            return Err("Type a name for the new profile".to_string());
+        }
+        if Self::profile_names().iter().any(|existing| existing.eq_ignore_ascii_case(&profile)) {
+            return Err(format!("There's already a profile called {}", profile));
+        }
+        let mut next_app = Self::from_parts(
+            vec![Wheel::new("Wheel 1".to_string())],
+            0,
+            AppSettings::default(),
+            SpinRng::default(),
+            EditLock::default(),
+        );
+        next_app.profile = profile.clone();
+        self.replace_with_profile(next_app);
+        // written now so it shows up in the list straight away
+        self.save_data();
+        Ok(profile)
+    }
+
+    // the Default profile can't go, deleting the open one falls back to it
+    fn delete_profile(&mut self, profile: &str) -> Result<(), String> {
+        if profile == DEFAULT_PROFILE {
+            return Err("The Default profile can't be deleted".to_string());
+        }
+        if self.test_mode {
+            return Err("Couldn't delete profile: test mode doesn't touch files".to_string());
+        }
+        if profile == self.profile {
+            let default_app = Self::load_profile(DEFAULT_PROFILE.to_string());
+            self.replace_with_profile(default_app);
+        }
+        fs::remove_file(Self::save_path(profile)).map_err(|err| format!("Couldn't delete profile {}: {}", profile, err))
+    }
+}
+
+// built in slice colours for when there's no palette file loaded
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+enum ColorScheme {
+    #[default]
+    Rainbow,
+    ColorBlindSafe,
+    Grayscale,
+}
+
+impl ColorScheme {
+    fn label(self) -> &'static str {
+        match self {
+            ColorScheme::Rainbow => "Default",
+            ColorScheme::ColorBlindSafe => "Color-blind safe",
+            ColorScheme::Grayscale => "Grayscale",
+        }
+    }
+
+    fn color(self, item_index: usize, number_of_items: usize) -> egui::Color32 {
+        match self {
+            ColorScheme::Rainbow => index_color(item_index, number_of_items),
+            ColorScheme::ColorBlindSafe => color_blind_safe_color(item_index),
+            ColorScheme::Grayscale => {
+                let gray = GRAYSCALE_SHADES[item_index % GRAYSCALE_SHADES.len()];
+                egui::Color32::from_gray(gray)
+            }
+        }
+    }
+}
+
+// okabe-ito, the usual set that stays apart for the common kinds of colour blindness
+const OKABE_ITO: [[u8; 3]; 8] = [
+    [230, 159, 0],
+    [86, 180, 233],
+    [0, 158, 115],
+    [240, 228, 66],
+    [0, 114, 178],
+    [213, 94, 0],
+    [204, 121, 167],
+    [0, 0, 0],
+];
+
+// light and dark mixed up so neighbours never end up close, all dark enough for white labels
+const GRAYSCALE_SHADES: [u8; 6] = [45, 125, 80, 150, 60, 105];
+
+// past the 8 colours each lap round gets lighter or darker, so the repeats still stand apart
+fn color_blind_safe_color(item_index: usize) -> egui::Color32 {
+    let [red, green, blue] = OKABE_ITO[item_index % OKABE_ITO.len()];
+    let lap = item_index / OKABE_ITO.len();
+    let shift = |channel: u8| match lap % 3 {
+        0 => channel,
+        1 => (channel as f32 + (255.0 - channel as f32) * 0.4) as u8,
+        _ => (channel as f32 * 0.6) as u8,
+    };
+    egui::Color32::from_rgb(shift(red), shift(green), shift(blue))
+}
+
+// slice colors, the default just walks the hue around by index
+fn index_color(item_index: usize, number_of_items: usize) -> egui::Color32 {
+    let hue = item_index as f32 / number_of_items as f32;
+    let red_amount = (255.0 * (hue * 6.0).sin().abs()) as u8;
+    let green_amount = (255.0 * ((hue * 6.0) + 2.0).sin().abs()) as u8;
+    let blue_amount = (255.0 * ((hue * 6.0) + 4.0).sin().abs()) as u8;
+    egui::Color32::from_rgb(red_amount, green_amount, blue_amount)
+}
+
+// wcag relative luminance, 0 for black up to 1 for white
+fn relative_luminance(color: [u8; 3]) -> f32 {
+    let channel = |value: u8| {
+        let value = value as f32 / 255.0;
+        if value <= 0.03928 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+    };
+    0.2126 * channel(color[0]) + 0.7152 * channel(color[1]) + 0.0722 * channel(color[2])
+}
+
+// contrast of the white slice labels on this colour, 3 is the usual floor for big text
+fn contrast_with_white(color: [u8; 3]) -> f32 {
+    1.05 / (relative_luminance(color) + 0.05)
+}
+
+const MIN_LABEL_CONTRAST: f32 = 3.0;
+
+// smallest a slice label shrinks to before it gets cut short instead
+const MIN_LABEL_FONT_SIZE: f32 = 9.0;
+
+// shrinks the label to fit max_width, and past the smallest size chops it with an ellipsis
+fn fit_slice_label(painter: &egui::Painter, name: &str, font_size: f32, max_width: f32) -> (String, f32) {
+    let text_width = |text: &str, size: f32| painter.layout_no_wrap(text.to_string(), egui::FontId::proportional(size), egui::Color32::WHITE).size().x;
+    let full_width = text_width(name, font_size);
+    if full_width <= max_width {
+        return (name.to_string(), font_size);
+    }
+    let shrunk_size = font_size * max_width / full_width;
+    if shrunk_size >= MIN_LABEL_FONT_SIZE {
+        return (name.to_string(), shrunk_size);
+    }
+    let mut kept_chars: Vec<char> = name.chars().collect();
+    while !kept_chars.is_empty() {
+        kept_chars.pop();
+        let shortened: String = kept_chars.iter().collect::<String>().trim_end().to_string() + "…";
+        if text_width(&shortened, MIN_LABEL_FONT_SIZE) <= max_width {
+            return (shortened, MIN_LABEL_FONT_SIZE);
+        }
+    }
+    ("…".to_string(), MIN_LABEL_FONT_SIZE)
+}
+
+// same hue, darkened until white labels read on it
+fn readable_version(color: [u8; 3]) -> [u8; 3] {
+    let mut darker = color;
+    while contrast_with_white(darker) < MIN_LABEL_CONTRAST {
+        darker = darker.map(|value| (value as f32 * 0.9) as u8);
+    }
+    darker
+}
+
+// heatmap goes cold (blue) for low weights to hot (red) for the biggest one
+fn heatmap_color(weight_fraction: f32) -> egui::Color32 {
+    let palette = [
+        [40, 90, 220],
+        [40, 190, 210],
+        [60, 200, 90],
+        [240, 210, 50],
+        [230, 60, 40],
+    ];
+    let scaled = weight_fraction.clamp(0.0, 1.0) * (palette.len() - 1) as f32;
+    let low_index = (scaled.floor() as usize).min(palette.len() - 2);
+    let blend = scaled - low_index as f32;
+    let low_color = palette[low_index];
+    let high_color = palette[low_index + 1];
+    let mix = |channel: usize| {
+        (low_color[channel] as f32 + (high_color[channel] as f32 - low_color[channel] as f32) * blend).round() as u8
+    };
+    egui::Color32::from_rgb(mix(0), mix(1), mix(2))
+}
+
+// left panel controls, shown in the side panel or the stacked drawer on narrow windows
+impl WheelApp {
+    fn show_controls(&mut self, ui: &mut egui::Ui, something_changed: &mut bool) {
+        self.wheels[self.current].sync_pct_bufs();
+        self.show_add_items_section(ui, something_changed);
+
+        ui.add_space(10.0);
+        self.show_items_section(ui, something_changed);
+
+        ui.add_space(10.0);
+        self.show_spin_buttons(ui, something_changed);
+
+        ui.add_space(5.0);
+        self.show_spin_options(ui, something_changed);
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(5.0);
+
+        self.show_queue_section(ui);
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(5.0);
+
+        self.show_archive_section(ui);
+
+        ui.add_space(5.0);
+
+        self.show_removed_section(ui, something_changed);
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        self.show_settings_section(ui, something_changed);
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+
+        self.show_history_section(ui);
+
+        self.show_stats_section(ui);
+    }
+
+    // the app wide lock or this wheel's own
+    fn editing_locked(&self) -> bool {
+        self.edit_lock.locked || self.wheels[self.current].data.locked
+    }
+
+    // wheel name, the add box and the bulk ways in (csv, pasted lists, json)
+    fn show_add_items_section(&mut self, ui: &mut egui::Ui, something_changed: &mut bool) {
+        let test_mode = self.test_mode;
+        let editing_locked = self.editing_locked();
+        let current_wheel = &mut self.wheels[self.current];
+        ui.add_enabled_ui(!editing_locked, |ui| {
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Wheel Name:");
+                if ui.text_edit_singleline(&mut current_wheel.data.name).changed() {
+                    *something_changed = true;
+                }
+            });
+
+            ui.add_space(10.0);
+            ui.separator();
+            ui.add_space(10.0);
+
+            ui.heading("Add Items");
+            ui.horizontal(|ui| {
+                let notation_hint = "Add a weight in brackets: \"sword (3)\", dice \"sword (2d6)\" or \"(d20+1)\" uses the average roll, \"sword (1:4)\" is a 1 in 4 chance";
+                let text_box_response = ui.text_edit_singleline(&mut current_wheel.state.input_text).on_hover_text(notation_hint);
+                if text_box_response.changed() {
+                    current_wheel.state.duplicate_warning = None;
+                }
+                let pressed_enter = text_box_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let clicked_add = ui.button("Add").clicked();
+                let has_text = !current_wheel.state.input_text.trim().is_empty();
+
+                let new_item_text = current_wheel.state.input_text.trim().to_string();
+                let new_item_name = split_weight_notation(&new_item_text).map_or(new_item_text.as_str(), |(name, _)| name);
+                let is_duplicate = current_wheel.rejects_name(new_item_name);
+                if (pressed_enter || clicked_add) && has_text && is_duplicate {
+                    // text stays in the box so it can be tweaked instead of typed again
+                    current_wheel.state.duplicate_warning = Some(format!("\"{}\" is already on this wheel", new_item_name));
+                } else if (pressed_enter || clicked_add) && has_text {
+                    current_wheel.push_undo();
+                    current_wheel.state.duplicate_warning = None;
+                    match split_weight_notation(&new_item_text) {
+                        Some((new_item_name, notation)) => match parse_weight_notation(notation) {
+                            Some(notation_weight) => current_wheel.add_item_with_weight(new_item_name.to_string(), notation_weight),
+                            None => {
+                                self.status_message = Some(format!("Couldn't read \"{}\" as a weight, added {} with weight 1", notation, new_item_name));
+                                current_wheel.add_item_with_weight(new_item_name.to_string(), 1);
+                            }
+                        },
+                        None => current_wheel.add_item(new_item_text),
+                    }
+                    current_wheel.state.input_text.clear();
+                    *something_changed = true;
+                }
+            });
+            if let Some(duplicate_warning) = &current_wheel.state.duplicate_warning {
+                ui.colored_label(ui.visuals().warn_fg_color, duplicate_warning);
+            }
+            if ui
+                .checkbox(&mut current_wheel.data.allow_duplicates, "Allow duplicate names")
+                .on_hover_text("Let the same name go on the wheel more than once")
+                .changed()
+            {
+                current_wheel.state.duplicate_warning = None;
+                *something_changed = true;
+            }
+            ui.horizontal(|ui| {
+                if ui.button("Import CSV").on_hover_text("Add items from a name,weight file").clicked()
+                    && let Some(csv_path) = rfd::FileDialog::new().add_filter("CSV", &["csv"]).pick_file()
+                {
+                    self.status_message = Some(match fs::read_to_string(&csv_path) {
+                        Ok(csv_text) => {
+                            let (imported_items, skipped_rows) = Wheel::from_csv(&csv_text);
+                            current_wheel.push_undo();
+                            let (imported_count, duplicate_count) = current_wheel.add_whole_items(imported_items);
+                            *something_changed = true;
+                            let mut import_summary = format!("Imported {} items", imported_count);
+                            if duplicate_count > 0 {
+                                import_summary.push_str(&format!(", skipped {} already on the wheel", duplicate_count));
+                            }
+                            if skipped_rows > 0 {
+                                import_summary.push_str(&format!(", skipped {} bad rows", skipped_rows));
+                            }
+                            import_summary
+                        }
+                        Err(err) => format!("Couldn't read {}: {}", csv_path.display(), err),
+                    });
+                }
+                if ui.add_enabled(!current_wheel.data.items.is_empty(), egui::Button::new("Export CSV")).clicked()
+                    && let Some(csv_path) = rfd::FileDialog::new()
+                        .add_filter("CSV", &["csv"])
+                        .set_file_name(format!("{}.csv", file_safe_name(&current_wheel.data.name)))
+                        .save_file()
+                {
+                    self.status_message = Some(match write_app_file(test_mode, &csv_path, current_wheel.to_csv().as_bytes()) {
+                        Ok(()) => format!("Items saved to {}", csv_path.display()),
+                        Err(err) => format!("Couldn't save {}: {}", csv_path.display(), err),
+                    });
+                }
+            });
+            ui.collapsing("Paste a list", |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut current_wheel.state.bulk_input_text)
+                        .hint_text("one item per line\npizza, 3\ntacos")
+                        .desired_rows(4)
+                        .desired_width(f32::INFINITY)
+                );
+                if ui.add_enabled(!current_wheel.state.bulk_input_text.trim().is_empty(), egui::Button::new("Add All")).clicked() {
+                    let pasted_text = std::mem::take(&mut current_wheel.state.bulk_input_text);
+                    current_wheel.push_undo();
+                    let (added_count, duplicate_count) = current_wheel.add_items_bulk(&pasted_text);
+                    self.status_message = Some(if duplicate_count > 0 {
+                        format!("Added {} items, skipped {} already on the wheel", added_count, duplicate_count)
+                    } else {
+                        format!("Added {} items", added_count)
+                    });
+                    *something_changed = true;
+                }
+            });
+            ui.collapsing("What are my chances?", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("An item at weight");
+                    ui.add(egui::DragValue::new(&mut current_wheel.state.what_if_weight).range(0..=100_000));
+                });
+                // same sums the wheel does, just with the extra item counted in
+                let existing_total = if current_wheel.data.items.is_empty() { 0 } else { current_wheel.total_weight() };
+                let what_if_weight = current_wheel.state.what_if_weight;
+                let new_total = (existing_total + what_if_weight).max(1) as f32;
+                ui.label(format!("would get {:.1}% of the wheel", what_if_weight as f32 / new_total * 100.0));
+                const SHOWN_ITEMS: usize = 5;
+                for item in current_wheel.data.items.iter().take(SHOWN_ITEMS) {
+                    let old_pct = item.weight as f32 / existing_total.max(1) as f32 * 100.0;
+                    let new_pct = item.weight as f32 / new_total * 100.0;
+                    ui.weak(format!("{}: {:.1}% → {:.1}%", item.name, old_pct, new_pct));
+                }
+                if current_wheel.data.items.len() > SHOWN_ITEMS {
+                    ui.weak(format!("...and {} more", current_wheel.data.items.len() - SHOWN_ITEMS));
+                }
+            });
+            ui.collapsing("Paste JSON", |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut current_wheel.state.json_import_buf)
+                        .hint_text("[{\"name\": \"a\", \"weight\": 3}]")
+                        .desired_rows(3)
+                        .desired_width(f32::INFINITY)
+                );
+                if ui.add_enabled(!current_wheel.state.json_import_buf.trim().is_empty(), egui::Button::new("Import")).clicked() {
+                    let json_text = std::mem::take(&mut current_wheel.state.json_import_buf);
+                    let before_import = current_wheel.data.clone();
+                    match current_wheel.import_json(&json_text) {
+                        Ok((imported_count, duplicate_count)) => {
+                            current_wheel.push_undo_snapshot(before_import);
+                            self.status_message = Some(if duplicate_count > 0 {
+                                format!("Imported {} items, skipped {} already on the wheel", imported_count, duplicate_count)
+                            } else {
+                                format!("Imported {} items", imported_count)
+                            });
+                            *something_changed = true;
+                        }
+                        Err(err) => {
+                            // keep the text so it can be fixed up
+                            current_wheel.state.json_import_buf = json_text;
+                            self.status_message = Some(format!("Couldn't read that JSON: {}", err));
+                        }
+                    }
+                }
+            });
+        });
+    }
+
+    // the item list itself with its sort, filter and balance controls
+    fn show_items_section(&mut self, ui: &mut egui::Ui, something_changed: &mut bool) {
+        let mut requested_action = None;
+        let editing_locked = self.editing_locked();
+        let current_wheel = &mut self.wheels[self.current];
+        ui.add_enabled_ui(!editing_locked, |ui| {
+            ui.heading(format!("Items ({})", current_wheel.data.items.len()));
+            let tied_items = current_wheel.top_weight_ties();
+            if current_wheel.data.items.len() > 1 && tied_items > 1 {
+                ui.weak(format!("{} items tied at highest odds", tied_items));
+            }
+            ui.horizontal(|ui| {
+                ui.label("Edit as:");
+                let mut weight_mode = current_wheel.data.weight_mode;
+                ui.selectable_value(&mut weight_mode, false, "Percent");
+                ui.selectable_value(&mut weight_mode, true, "Weight").on_hover_text("Type exact whole number weights");
+                if weight_mode != current_wheel.data.weight_mode {
+                    current_wheel.set_weight_mode(weight_mode);
+                    *something_changed = true;
+                }
+            });
+            if current_wheel.has_boosts() {
+                ui.horizontal(|ui| {
+                    ui.colored_label(egui::Color32::from_rgb(255, 160, 0), "⚡ Boosts apply to the next spin");
+                    if ui.small_button("Clear boosts").clicked() {
+                        for item in current_wheel.data.items.iter_mut() {
+                            item.boost = 1;
+                        }
+                    }
+                });
+            }
 
-        let spin_just_finished = self.wheels[self.current].tick(dt);
-        if spin_just_finished {
-            self.needs_save = true;
+            ui.horizontal(|ui| {
+                ui.label("Sort:");
+                let sort_before = current_wheel.data.sort_mode;
+                egui::ComboBox::from_id_salt("sort_mode")
+                    .selected_text(current_wheel.data.sort_mode.label())
+                    .show_ui(ui, |ui| {
+                        for sort_mode in [SortMode::Manual, SortMode::NameAscending, SortMode::WeightDescending] {
+                            ui.selectable_value(&mut current_wheel.data.sort_mode, sort_mode, sort_mode.label());
+                        }
+                    });
+                if current_wheel.data.sort_mode != sort_before {
+                    *something_changed = true;
+                }
+                if current_wheel.data.sort_mode != SortMode::Manual
+                    && ui.checkbox(&mut current_wheel.data.view_only_sort, "View only").on_hover_text("Sort the list without changing the wheel").changed()
+                {
+                    *something_changed = true;
+                }
+            });
+            if !current_wheel.data.view_only_sort && !current_wheel.state.is_spinning && current_wheel.apply_sort() {
+                *something_changed = true;
+            }
+            ui.horizontal(|ui| {
+                ui.label("Odds preset:");
+                let mut preset_to_apply = None;
+                let mut preset_to_delete = None;
+                egui::ComboBox::from_id_salt("weight_preset")
+                    .selected_text(if current_wheel.data.weight_presets.is_empty() { "(none saved)" } else { "Apply..." })
+                    .width(90.0)
+                    .show_ui(ui, |ui| {
+                        for (preset_name, _) in &current_wheel.data.weight_presets {
+                            ui.horizontal(|ui| {
+                                if ui.selectable_label(false, preset_name).clicked() {
+                                    preset_to_apply = Some(preset_name.clone());
+                                }
+                                if ui.small_button("🗑").on_hover_text("Delete preset").clicked() {
+                                    preset_to_delete = Some(preset_name.clone());
+                                }
+                            });
+                        }
+                    });
+                ui.add(
+                    egui::TextEdit::singleline(&mut current_wheel.state.preset_name_input)
+                        .hint_text("preset name")
+                        .desired_width(80.0)
+                );
+                let preset_name = current_wheel.state.preset_name_input.trim().to_string();
+                let overwrites = current_wheel.data.weight_presets.iter().any(|(existing_name, _)| *existing_name == preset_name);
+                let save_text = if overwrites { "Overwrite" } else { "Save" };
+                if ui.add_enabled(!preset_name.is_empty(), egui::Button::new(save_text)).clicked() {
+                    current_wheel.save_weight_preset(preset_name);
+                    current_wheel.state.preset_name_input.clear();
+                    *something_changed = true;
+                }
+                if let Some(preset_name) = preset_to_apply {
+                    current_wheel.apply_weight_preset(&preset_name);
+                    *something_changed = true;
+                }
+                if let Some(preset_name) = preset_to_delete {
+                    current_wheel.data.weight_presets.retain(|(existing_name, _)| *existing_name != preset_name);
+                    *something_changed = true;
+                }
+            });
+            let mut display_order = current_wheel.sorted_order();
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut current_wheel.state.item_filter).hint_text("🔍 filter items"));
+                if !current_wheel.state.item_filter.is_empty() && ui.small_button("✖").on_hover_text("Show every item").clicked() {
+                    current_wheel.state.item_filter.clear();
+                }
+            });
+            // rows keep their real indices, hidden ones are just skipped
+            let item_filter = current_wheel.state.item_filter.trim().to_lowercase();
+            let filtering = !item_filter.is_empty();
+            if filtering {
+                let items = &current_wheel.data.items;
+                display_order.retain(|&item_index| items[item_index].name.to_lowercase().contains(&item_filter));
+                ui.weak(format!("Showing {} of {}", display_order.len(), items.len()));
+            }
+
+            egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+                let mut remove_temp: Option<usize> = None;
+                let mut remove_perm: Option<usize> = None;
+                let mut should_commit_edit = false;
+                let mut apply_pct_for_index: Option<usize> = None;
+                let mut swap_with_next: Option<usize> = None;
+                // moving only makes sense when the list is the wheel order, and a filtered list
+                // would swap with rows you can't see
+                let can_reorder = current_wheel.data.sort_mode == SortMode::Manual && !current_wheel.state.is_spinning && !filtering;
+                let last_item_index = current_wheel.data.items.len().saturating_sub(1);
+                let total_weight = current_wheel.total_weight();
+                let total_effective_weight = current_wheel.total_effective_weight();
+
+                for item_index in display_order {
+                    let item_pct = current_wheel.data.items[item_index].weight as f32 / total_weight as f32 * 100.0;
+
+                    if current_wheel.state.pct_bufs[item_index].is_empty() {
+                        current_wheel.state.pct_bufs[item_index] = current_wheel.weight_field_text(item_index, item_pct);
+                    }
+
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut current_wheel.data.items[item_index].selected, "")
+                            .on_hover_text("Select for Spin Selected");
+                        let currently_editing_this_item = current_wheel.state.editing_idx == Some(item_index);
+                        if currently_editing_this_item {
+                            let edit_response = ui.add(
+                                egui::TextEdit::singleline(&mut current_wheel.state.edit_buf).desired_width(80.0)
+                            );
+                            let pressed_enter = ui.input(|inp| inp.key_pressed(egui::Key::Enter));
+                            if edit_response.lost_focus() || pressed_enter {
+                                should_commit_edit = true;
+                            }
+                            edit_response.request_focus();
+                        } else {
+                            let item_name_text = if current_wheel.data.items[item_index].weight == 0 {
+                                egui::RichText::new(&current_wheel.data.items[item_index].name).weak()
+                            } else {
+                                egui::RichText::new(&current_wheel.data.items[item_index].name)
+                            };
+                            let item_label = ui.add(egui::Label::new(item_name_text).sense(egui::Sense::click()));
+                            if item_label.double_clicked() {
+                                current_wheel.state.editing_idx = Some(item_index);
+                                current_wheel.state.edit_buf = current_wheel.data.items[item_index].name.clone();
+                            }
+                            let wheel_cooldown = current_wheel.data.winner_cooldown;
+                            item_label.context_menu(|ui| {
+                                ui.horizontal(|ui| {
+                                    let item_color = &mut current_wheel.data.items[item_index].color;
+                                    let mut custom_color = item_color.is_some();
+                                    if ui.checkbox(&mut custom_color, "Own colour").changed() {
+                                        *item_color = if custom_color { Some([100, 150, 200]) } else { None };
+                                        *something_changed = true;
+                                    }
+                                    if let Some(picked_color) = item_color {
+                                        if ui.color_edit_button_srgb(picked_color).changed() {
+                                            *something_changed = true;
+                                        }
+                                        // just advice, the colour is kept either way
+                                        if contrast_with_white(*picked_color) < MIN_LABEL_CONTRAST {
+                                            let suggested_color = readable_version(*picked_color);
+                                            ui.colored_label(egui::Color32::from_rgb(255, 160, 0), "⚠")
+                                                .on_hover_text("White labels will be hard to read on this colour");
+                                            let [red, green, blue] = suggested_color;
+                                            let suggestion_text = egui::RichText::new("Aa")
+                                                .color(egui::Color32::WHITE)
+                                                .background_color(egui::Color32::from_rgb(red, green, blue));
+                                            if ui.small_button(suggestion_text).on_hover_text("Use this darker shade instead").clicked() {
+                                                *picked_color = suggested_color;
+                                                *something_changed = true;
+                                            }
+                                        }
+                                    }
+                                });
+                                let cooldown = &mut current_wheel.data.items[item_index].cooldown;
+                                let mut own_cooldown = cooldown.is_some();
+                                if ui.checkbox(&mut own_cooldown, "Own cooldown").changed() {
+                                    *cooldown = if own_cooldown { Some(wheel_cooldown) } else { None };
+                                    *something_changed = true;
+                                }
+                                if let Some(cooldown_spins) = cooldown {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Sit out");
+                                        if ui.add(egui::DragValue::new(cooldown_spins).range(0..=50)).changed() {
+                                            *something_changed = true;
+                                        }
+                                        ui.label("spins after winning");
+                                    });
+                                }
+                            });
+                            item_label.on_hover_text("Double-click to rename, right-click for colour and cooldown");
+                            let cooldown_left = current_wheel.data.items[item_index].cooldown_left;
+                            if cooldown_left > 0 {
+                                ui.weak(format!("⏳{}", cooldown_left))
+                                    .on_hover_text(format!("Can't win for {} more spin(s)", cooldown_left));
+                            }
+                        }
+
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("🗑").on_hover_text("Delete forever").clicked() {
+                                remove_perm = Some(item_index);
+                            }
+                            if ui.small_button("❌").on_hover_text("Remove temporarily").clicked() {
+                                remove_temp = Some(item_index);
+                            }
+                            if ui.add_enabled(can_reorder && item_index < last_item_index, egui::Button::new("▼").small()).clicked() {
+                                swap_with_next = Some(item_index);
+                            }
+                            if ui.add_enabled(can_reorder && item_index > 0, egui::Button::new("▲").small()).clicked() {
+                                swap_with_next = Some(item_index - 1);
+                            }
+                            let item_is_protected = current_wheel.data.items[item_index].protected;
+                            let lock_symbol = if item_is_protected { "🔒" } else { "🔓" };
+                            let lock_hint = if item_is_protected {
+                                "Protected: never removed after winning"
+                            } else {
+                                "Protect from elimination"
+                            };
+                            if ui.small_button(lock_symbol).on_hover_text(lock_hint).clicked() {
+                                current_wheel.data.items[item_index].protected = !item_is_protected;
+                                *something_changed = true;
+                            }
+                            let item_boost = current_wheel.data.items[item_index].boost;
+                            let boost_text = if item_boost > 1 {
+                                egui::RichText::new(format!("⚡×{}", item_boost)).color(egui::Color32::from_rgb(255, 160, 0))
+                            } else {
+                                egui::RichText::new("⚡")
+                            };
+                            let boosted_pct = current_wheel.data.items[item_index].effective_weight() as f32
+                                / total_effective_weight as f32
+                                * 100.0;
+                            let boost_hint = format!("Boost for the next spin only ({:.0}% next spin)", boosted_pct);
+                            if ui.small_button(boost_text).on_hover_text(boost_hint).clicked() {
+                                current_wheel.data.items[item_index].cycle_boost();
+                            }
+                            let has_image = current_wheel.data.items[item_index].image_path.is_some();
+                            let image_text = if has_image { egui::RichText::new("🖼").strong() } else { egui::RichText::new("🖼").weak() };
+                            if ui.small_button(image_text).on_hover_text("Slice image").clicked() {
+                                if current_wheel.state.image_edit_idx == Some(item_index) {
+                                    current_wheel.state.image_edit_idx = None;
+                                } else {
+                                    current_wheel.state.image_edit_idx = Some(item_index);
+                                    current_wheel.state.image_path_buf =
+                                        current_wheel.data.items[item_index].image_path.clone().unwrap_or_default();
+                                }
+                            }
+                            let item_notes = current_wheel.data.items[item_index].notes.trim();
+                            let (notes_text, notes_hint) = if item_notes.is_empty() {
+                                (egui::RichText::new("ℹ").weak(), "Add notes, they show when hovering the slice".to_string())
+                            } else {
+                                (egui::RichText::new("ℹ").strong(), item_notes.to_string())
+                            };
+                            if ui.small_button(notes_text).on_hover_text(notes_hint).clicked() {
+                                current_wheel.state.notes_edit_idx =
+                                    if current_wheel.state.notes_edit_idx == Some(item_index) { None } else { Some(item_index) };
+                            }
+                            // swatch for an own colour, the right-click menu has the contrast check and reset
+                            let shown_color = current_wheel.slice_color(item_index, self.slice_palette.as_ref(), self.settings.color_scheme);
+                            match &mut current_wheel.data.items[item_index].color {
+                                Some(picked_color) => {
+                                    if ui.color_edit_button_srgb(picked_color).on_hover_text("Slice colour, right-click the name to go back to automatic").changed() {
+                                        *something_changed = true;
+                                    }
+                                }
+                                item_color @ None => {
+                                    let swatch_text = egui::RichText::new("■").color(shown_color);
+                                    if ui.small_button(swatch_text).on_hover_text("Pick a colour for this slice").clicked() {
+                                        *item_color = Some([shown_color.r(), shown_color.g(), shown_color.b()]);
+                                        *something_changed = true;
+                                    }
+                                }
+                            }
+
+                            let weight_mode = current_wheel.data.weight_mode;
+                            ui.label(if weight_mode { "wt" } else { "%" });
+
+                            let weight_field_hint = if weight_mode {
+                                format!("Exact weight, at least 1 ({:.1}% now)", item_pct)
+                            } else {
+                                "0 keeps it on the list but it can't win".to_string()
+                            };
+                            let pct_box_response = ui.add(
+                                egui::TextEdit::singleline(&mut current_wheel.state.pct_bufs[item_index])
+                                    .desired_width(36.0)
+                                    .horizontal_align(egui::Align::RIGHT)
+                            ).on_hover_text(weight_field_hint);
+                            let pressed_enter = ui.input(|inp| inp.key_pressed(egui::Key::Enter));
+                            if pct_box_response.lost_focus() || pressed_enter {
+                                apply_pct_for_index = Some(item_index);
+                            }
+                            if pct_box_response.gained_focus() {
+                                current_wheel.state.pct_bufs[item_index] = current_wheel.weight_field_text(item_index, item_pct);
+                            }
+                        });
+                    });
+
+                    if current_wheel.state.image_edit_idx == Some(item_index) {
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut current_wheel.state.image_path_buf)
+                                    .hint_text("path to a png/jpg")
+                                    .desired_width(170.0)
+                            );
+                            if ui.small_button("Set").clicked() {
+                                let image_path = current_wheel.state.image_path_buf.trim().to_string();
+                                current_wheel.data.items[item_index].image_path = if image_path.is_empty() { None } else { Some(image_path) };
+                                current_wheel.state.image_edit_idx = None;
+                                *something_changed = true;
+                            }
+                            if ui.small_button("Clear").clicked() {
+                                current_wheel.data.items[item_index].image_path = None;
+                                current_wheel.state.image_edit_idx = None;
+                                *something_changed = true;
+                            }
+                        });
+                    }
+
+                    if current_wheel.state.notes_edit_idx == Some(item_index) {
+                        let notes_box = egui::TextEdit::multiline(&mut current_wheel.data.items[item_index].notes)
+                            .hint_text("notes for this item")
+                            .desired_rows(2)
+                            .desired_width(f32::INFINITY);
+                        if ui.add(notes_box).changed() {
+                            *something_changed = true;
+                        }
+                        if ui.small_button("Done").clicked() {
+                            current_wheel.state.notes_edit_idx = None;
+                        }
+                    }
+                }
+
+                if let Some(item_index) = swap_with_next {
+                    current_wheel.push_undo();
+                    current_wheel.swap_items(item_index, item_index + 1);
+                    *something_changed = true;
+                }
+
+                let mut restore_index: Option<usize> = None;
+                if self.settings.show_removed_inline {
+                    for (removed_index, removed_item) in current_wheel.data.removed_items.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(&removed_item.name).strikethrough().color(egui::Color32::GRAY));
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.small_button("↩").on_hover_text("Restore").clicked() {
+                                    restore_index = Some(removed_index);
+                                }
+                            });
+                        });
+                    }
+                }
+                if let Some(removed_index) = restore_index {
+                    current_wheel.push_undo();
+                    current_wheel.restore_item(removed_index);
+                    *something_changed = true;
+                }
+
+                if let Some(item_index) = apply_pct_for_index {
+                    let before_weight_change = current_wheel.data.clone();
+                    let did_apply = current_wheel.apply_weight_field(item_index);
+                    if did_apply {
+                        current_wheel.push_undo_snapshot(before_weight_change);
+                        for buf in current_wheel.state.pct_bufs.iter_mut() {
+                            buf.clear();
+                        }
+                        *something_changed = true;
+                    }
+                }
+
+                if should_commit_edit {
+                    if let Some(editing_index) = current_wheel.state.editing_idx {
+                        let new_name = current_wheel.state.edit_buf.trim().to_string();
+                        if !new_name.is_empty() && new_name != current_wheel.data.items[editing_index].name {
+                            current_wheel.push_undo();
+                            current_wheel.data.items[editing_index].name = new_name;
+                            *something_changed = true;
+                        }
+                    }
+                    current_wheel.state.editing_idx = None;
+                    current_wheel.state.edit_buf.clear();
+                }
+
+                if let Some(item_index) = remove_perm {
+                    requested_action = Some(PendingAction::DeleteItem(item_index));
+                }
+                if let Some(item_index) = remove_temp {
+                    current_wheel.push_undo();
+                    if current_wheel.state.editing_idx == Some(item_index) {
+                        current_wheel.state.editing_idx = None;
+                    }
+                    current_wheel.move_to_removed(item_index);
+                    for buf in current_wheel.state.pct_bufs.iter_mut() {
+                        buf.clear();
+                    }
+                    *something_changed = true;
+                }
+            });
+            let shown_pct_total = current_wheel.shown_pct_total();
+            if shown_pct_total > 0 {
+                ui.horizontal(|ui| {
+                    let balance_hint = "Set the weights so the percentages are whole numbers that add up to exactly 100";
+                    if ui.add_enabled(shown_pct_total != 100, egui::Button::new("Balance %")).on_hover_text(balance_hint).clicked() {
+                        let before_balance = current_wheel.data.clone();
+                        if current_wheel.balance_percentages() {
+                            current_wheel.push_undo_snapshot(before_balance);
+                            *something_changed = true;
+                        }
+                    }
+                    if shown_pct_total != 100 {
+                        ui.weak(format!("Percentages add up to {}%", shown_pct_total));
+                    }
+                });
+            }
+        });
+
+        if let Some(action) = requested_action {
+            self.request_action(action);
+        }
+    }
+
+    // spin, stop and everything else that draws or takes a draw back
+    fn show_spin_buttons(&mut self, ui: &mut egui::Ui, something_changed: &mut bool) {
+        let test_mode = self.test_mode;
+        let mut requested_action = None;
+        let mut start_recording = false;
+        let editing_locked = self.editing_locked();
+        let current_wheel = &mut self.wheels[self.current];
+        ui.horizontal(|ui| {
+            if ui.add_enabled(current_wheel.can_spin(), egui::Button::new("🎲 SPIN!")).clicked() {
+                current_wheel.spin(&mut self.spin_rng);
+            }
+            let can_stop = current_wheel.state.is_spinning || current_wheel.state.queue_pause.is_some();
+            if ui.add_enabled(can_stop, egui::Button::new("⏹ Stop")).on_hover_text("Stop this spin and any chain or queue after it").clicked() {
+                current_wheel.stop_spinning();
+            }
+            let can_record = current_wheel.can_spin() && self.gif_progress.is_none();
+            if ui.add_enabled(can_record, egui::Button::new("🎞 Spin + GIF")).on_hover_text("Spin and save the animation as a GIF").clicked() {
+                start_recording = true;
+            }
+            // anything drawn while a result waits for accept or reject would land in the wrong place
+            let can_draw_now = !current_wheel.state.is_spinning && current_wheel.state.provisional_winner.is_none();
+            let selected_count = current_wheel.data.items.iter().filter(|item| item.selected).count();
+            if selected_count > 0 {
+                let spin_selected_button = egui::Button::new(format!("🎯 Spin Selected ({})", selected_count));
+                let spin_selected_hint = "Pick among the ticked items only, nothing gets removed";
+                if ui.add_enabled(can_draw_now, spin_selected_button).on_hover_text(spin_selected_hint).clicked()
+                    && let Some(winning_name) = current_wheel.spin_selected(&mut self.spin_rng)
+                {
+                    self.decide_flash = Some((winning_name, 0.0));
+                    *something_changed = true;
+                }
+                if ui.small_button("✖").on_hover_text("Clear selection").clicked() {
+                    for item in current_wheel.data.items.iter_mut() {
+                        item.selected = false;
+                    }
+                }
+            }
+            let can_certify = can_draw_now && !current_wheel.data.items.is_empty();
+            let certify_hint = "Draw instantly with a recorded seed and save a certificate of the result";
+            if ui.add_enabled(can_certify, egui::Button::new("📜 Certified Draw")).on_hover_text(certify_hint).clicked() {
+                self.status_message = Some(match current_wheel.certified_draw(&mut self.spin_rng, test_mode) {
+                    Ok(certificate_path) => format!("Certificate saved to {}", certificate_path.display()),
+                    Err(err) => format!("Couldn't save certificate: {}", err),
+                });
+                *something_changed = true;
+            }
+            let anything_to_clear = !current_wheel.data.items.is_empty() || !current_wheel.data.winner_history.is_empty();
+            if ui.add_enabled(!editing_locked, egui::Button::new("Clear All")).clicked() && anything_to_clear {
+                requested_action = Some(PendingAction::ClearAll);
+            }
+            if current_wheel.state.cleared_stash.is_some() && ui.add_enabled(!editing_locked, egui::Button::new("↩ Undo Clear")).clicked() {
+                current_wheel.undo_clear();
+                *something_changed = true;
+            }
+            let undo_spin_count = current_wheel.state.spin_undo.len();
+            let can_undo_spin = undo_spin_count > 0 && !current_wheel.state.is_spinning && !editing_locked;
+            if undo_spin_count > 0
+                && ui.add_enabled(can_undo_spin, egui::Button::new(format!("↶ Undo Spin ({})", undo_spin_count)))
+                    .on_hover_text("Take back the last result and put the winner back")
+                    .clicked()
+            {
+                if let Err(err) = current_wheel.undo_spin() {
+                    self.status_message = Some(format!("Couldn't undo the spin: {}", err));
+                }
+                *something_changed = true;
+            }
+            let can_undo_edit = !current_wheel.state.undo_stack.is_empty() && !current_wheel.state.is_spinning && !editing_locked;
+            if ui.add_enabled(can_undo_edit, egui::Button::new("↶ Undo Edit")).on_hover_text("Ctrl+Z").clicked() && current_wheel.undo_edit() {
+                *something_changed = true;
+            }
+            let can_redo_edit = !current_wheel.state.redo_stack.is_empty() && !current_wheel.state.is_spinning && !editing_locked;
+            if ui.add_enabled(can_redo_edit, egui::Button::new("↷ Redo Edit")).on_hover_text("Ctrl+Y").clicked() && current_wheel.redo_edit() {
+                *something_changed = true;
+            }
+        });
+
+        if let Some(action) = requested_action {
+            self.request_action(action);
+        }
+        if start_recording {
+            self.start_gif_recording();
+        }
+    }
+
+    // how spins behave, removal, cooldowns, pointer, seed and chaining
+    fn show_spin_options(&mut self, ui: &mut egui::Ui, something_changed: &mut bool) {
+        let editing_locked = self.editing_locked();
+        let current_wheel = &mut self.wheels[self.current];
+        ui.add_enabled_ui(!editing_locked, |ui| {
+            if ui.checkbox(&mut current_wheel.data.remove_winner, "Remove winner after spin").changed() {
+                *something_changed = true;
+            }
+            let in_place_hint = "Restored items go back to where they were in the list instead of the end";
+            if ui.checkbox(&mut current_wheel.data.restore_in_place, "Restore to original spot").on_hover_text(in_place_hint).changed() {
+                *something_changed = true;
+            }
+            if ui.checkbox(&mut current_wheel.data.auto_spin, "Keep spinning until one left").changed() {
+                *something_changed = true;
+            }
+            let confirm_hint = "Each result waits for Accept or Reject, only accepted ones are recorded";
+            if ui.checkbox(&mut current_wheel.data.confirm_winner, "Confirm each winner").on_hover_text(confirm_hint).changed() {
+                *something_changed = true;
+            }
+            ui.horizontal(|ui| {
+                ui.label("Winners sit out");
+                let cooldown_box = egui::DragValue::new(&mut current_wheel.data.winner_cooldown).range(0..=50);
+                let cooldown_hint = "The last few winners can't be picked again until this many spins have gone by, if everything is sitting out it's ignored. 0 turns it off, items can have their own from the right-click menu";
+                if ui.add(cooldown_box).on_hover_text(cooldown_hint).changed() {
+                    *something_changed = true;
+                }
+                ui.label("spins");
+            });
+            let repeat_hint = "The last winner can't win the very next spin";
+            if ui.checkbox(&mut current_wheel.data.no_immediate_repeats, "No winning twice in a row").on_hover_text(repeat_hint).changed() {
+                *something_changed = true;
+            }
+            ui.horizontal(|ui| {
+                ui.label("Pick");
+                let most_picks = current_wheel.data.items.len().max(1) as u32;
+                current_wheel.data.pick_count = current_wheel.data.pick_count.clamp(1, most_picks);
+                let pick_box = egui::DragValue::new(&mut current_wheel.data.pick_count).range(1..=most_picks);
+                let pick_hint = "The wheel lands on one and the rest are drawn from what's left, nothing gets removed when it's more than 1";
+                if ui.add(pick_box).on_hover_text(pick_hint).changed() {
+                    *something_changed = true;
+                }
+                ui.label("winners per spin");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Arrow:");
+                ui.add_enabled_ui(!current_wheel.state.is_spinning, |ui| {
+                    for pointer_pos in [PointerPos::Top, PointerPos::Right, PointerPos::Bottom, PointerPos::Left] {
+                        if ui.selectable_value(&mut current_wheel.data.pointer_pos, pointer_pos, pointer_pos.label()).changed() {
+                            *something_changed = true;
+                        }
+                    }
+                });
+            });
+            let can_flip = !current_wheel.state.is_spinning;
+            if ui.add_enabled(can_flip, egui::Checkbox::new(&mut current_wheel.data.clockwise, "Spin clockwise")).changed() {
+                *something_changed = true;
+            }
+            ui.horizontal(|ui| {
+                ui.label("Spin length:");
+                for spin_length in [SpinLength::Short, SpinLength::Medium, SpinLength::Long] {
+                    if ui.selectable_value(&mut current_wheel.data.spin_length, spin_length, spin_length.label()).changed() {
+                        *something_changed = true;
+                    }
+                }
+            });
+            let shuffle_hint = "Rearranges where the slices sit each spin, the odds stay the same";
+            if ui.checkbox(&mut current_wheel.data.shuffle_slices, "Shuffle slices each spin").on_hover_text(shuffle_hint).changed() {
+                *something_changed = true;
+            }
+            ui.horizontal(|ui| {
+                ui.label("Wheel seed");
+                let seed_box = egui::TextEdit::singleline(&mut current_wheel.state.seed_input).hint_text("random").desired_width(140.0);
+                let seed_hint = "Everyone with the same seed gets the same spins in the same order, leave empty for random";
+                if ui.add(seed_box).on_hover_text(seed_hint).changed() && current_wheel.set_seed_from_input() {
+                    *something_changed = true;
+                }
+                if current_wheel.data.seed.is_some() && ui.small_button("Restart").on_hover_text("Go back to the first spin of the seed").clicked() {
+                    current_wheel.state.seed_sequence = None;
+                }
+            });
+            let seed_text = current_wheel.state.seed_input.trim();
+            if !seed_text.is_empty() && seed_text.parse::<u64>().is_err() {
+                ui.colored_label(egui::Color32::from_rgb(255, 160, 0), "Seed has to be a whole number");
+            }
+            if current_wheel.data.auto_spin {
+                ui.horizontal(|ui| {
+                    ui.label("Need at least");
+                    let min_items_box = egui::DragValue::new(&mut current_wheel.data.min_elimination_items).range(0..=100);
+                    if ui.add(min_items_box).changed() {
+                        *something_changed = true;
+                    }
+                    ui.label("items to start");
+                });
+                if current_wheel.data.remove_winner && !current_wheel.elimination_ready() {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 160, 0),
+                        format!(
+                            "Elimination needs {} items (have {}), spins won't chain",
+                            current_wheel.data.min_elimination_items,
+                            current_wheel.data.items.len()
+                        ),
+                    );
+                }
+            }
+        });
+    }
+
+    // labelled spins run one after another, results listed underneath
+    fn show_queue_section(&mut self, ui: &mut egui::Ui) {
+        let current_wheel = &mut self.wheels[self.current];
+        ui.heading("Spin Queue");
+        ui.horizontal(|ui| {
+            let label_box_response = ui.add(
+                egui::TextEdit::singleline(&mut current_wheel.state.queue_input)
+                    .hint_text("label, e.g. Monday")
+                    .desired_width(150.0)
+            );
+            let pressed_enter = label_box_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let clicked_queue = ui.button("Queue").clicked();
+            let new_label = current_wheel.state.queue_input.trim().to_string();
+            if (pressed_enter || clicked_queue) && !new_label.is_empty() {
+                current_wheel.state.spin_queue.push_back(new_label);
+                current_wheel.state.queue_input.clear();
+            }
+        });
+        if !current_wheel.state.spin_queue.is_empty() {
+            let queued_labels: Vec<&str> = current_wheel.state.spin_queue.iter().map(|label| label.as_str()).collect();
+            ui.label(format!("Up next: {}", queued_labels.join(", ")));
+        }
+        ui.horizontal(|ui| {
+            let queue_running = current_wheel.state.active_queue_label.is_some() || current_wheel.state.queue_pause.is_some();
+            let can_run_queue = !current_wheel.state.spin_queue.is_empty()
+                && !current_wheel.state.is_spinning
+                && !queue_running
+                && current_wheel.data.items.len() >= 2;
+            if ui.add_enabled(can_run_queue, egui::Button::new("▶ Run Queue")).clicked() {
+                current_wheel.start_next_queued_spin(&mut self.spin_rng);
+            }
+            let has_queue = !current_wheel.state.spin_queue.is_empty() || queue_running;
+            if ui.add_enabled(has_queue, egui::Button::new("Cancel Queue")).clicked() {
+                current_wheel.cancel_queue();
+            }
+        });
+        if !current_wheel.state.queue_results.is_empty() {
+            egui::ScrollArea::vertical().id_salt("queue_results").max_height(100.0).show(ui, |ui| {
+                for (queue_label, winner_name) in &current_wheel.state.queue_results {
+                    ui.label(format!("{}: {}", queue_label, winner_name));
+                }
+            });
+            if ui.button("Clear Results").clicked() {
+                current_wheel.state.queue_results.clear();
+            }
+        }
+    }
+
+    // recording an event to a file and replaying one back
+    fn show_archive_section(&mut self, ui: &mut egui::Ui) {
+        let test_mode = self.test_mode;
+        let mut load_event_archive = false;
+        let current_wheel = &mut self.wheels[self.current];
+        ui.heading("Event Archive");
+        ui.horizontal(|ui| {
+            let recorded_spins = current_wheel.state.event_recording.as_ref().map(|event_recording| event_recording.spins.len());
+            match recorded_spins {
+                None => {
+                    let record_hint = "Keeps the starting list and every spin's seed so the whole event can be replayed";
+                    if ui.button("⏺ Start Recording").on_hover_text(record_hint).clicked() {
+                        current_wheel.start_event_recording();
+                    }
+                }
+                Some(spin_count) => {
+                    ui.colored_label(egui::Color32::from_rgb(230, 60, 40), format!("⏺ {} spins", spin_count));
+                    if ui.button("⏹ Stop & Export").clicked()
+                        && let Some(event_archive) = current_wheel.state.event_recording.take()
+                    {
+                        self.status_message = Some(
+                            match serde_json::to_string_pretty(&event_archive)
+                                .map_err(|err| err.to_string())
+                                .and_then(|archive_json| write_export(test_mode, &current_wheel.data.name, "event.json", &archive_json))
+                            {
+                                Ok(export_path) => format!("Event archive saved to {}", export_path.display()),
+                                Err(err) => format!("Couldn't save event archive: {}", err),
+                            },
+                        );
+                    }
+                    if ui.small_button("Discard").clicked() {
+                        current_wheel.state.event_recording = None;
+                    }
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.event_import_path)
+                    .hint_text("path to .event.json")
+                    .desired_width(150.0)
+            );
+            if ui.add_enabled(!self.event_import_path.trim().is_empty(), egui::Button::new("Load & Replay")).clicked() {
+                load_event_archive = true;
+            }
+        });
+        if let Some(replay) = &current_wheel.state.replay {
+            let replay_text = format!("Replay: {} left, {} matched, {} differ", replay.upcoming.len(), replay.matched, replay.mismatched);
+            ui.horizontal(|ui| {
+                ui.label(replay_text);
+                if ui.small_button("Stop").clicked() {
+                    current_wheel.state.replay = None;
+                }
+            });
         }
-        if self.wheels[self.current].state.is_spinning {
-            ctx.request_repaint();
+
+        if load_event_archive {
+            self.import_event_archive();
         }
+    }
 
-        egui::TopBottomPanel::top("top").show(ctx, |ui| {
+    // only shows up once something's been removed
+    fn show_removed_section(&mut self, ui: &mut egui::Ui, something_changed: &mut bool) {
+        let mut new_wheel_from_removed: Option<bool> = None;
+        let editing_locked = self.editing_locked();
+        let current_wheel = &mut self.wheels[self.current];
+        if !current_wheel.data.removed_items.is_empty() {
+            ui.separator();
+            ui.add_space(5.0);
             ui.horizontal(|ui| {
-                ui.heading("Gerbil Decide");
-                ui.separator();
-
-                let mut switch_to_wheel = None;
-                for (wheel_index, wheel) in self.wheels.iter().enumerate() {
-                    let is_selected = self.current == wheel_index;
-                    if ui.selectable_label(is_selected, &wheel.data.name).clicked() {
-                        switch_to_wheel = Some(wheel_index);
+                ui.heading(format!("Removed ({})", current_wheel.data.removed_items.len()));
+                let arrow_symbol = if self.show_removed { "▼" } else { "▶" };
+                if ui.small_button(arrow_symbol).clicked() {
+                    self.show_removed = !self.show_removed;
+                }
+            });
+            if self.show_removed && !self.settings.show_removed_inline {
+                // picked inside the loop, removed after it so the indices stay put while drawing
+                let mut restore_index: Option<usize> = None;
+                egui::ScrollArea::vertical().max_height(100.0).show(ui, |ui| {
+                    for (removed_index, removed_item) in current_wheel.data.removed_items.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(&removed_item.name);
+                            if ui.add_enabled(!editing_locked, egui::Button::new("↩").small()).on_hover_text("Restore just this one").clicked() {
+                                restore_index = Some(removed_index);
+                            }
+                        });
                     }
+                });
+                if let Some(removed_index) = restore_index {
+                    current_wheel.push_undo();
+                    current_wheel.restore_item(removed_index);
+                    *something_changed = true;
                 }
-                if let Some(wheel_index) = switch_to_wheel {
-                    self.current = wheel_index;
+            }
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!editing_locked, egui::Button::new("Restore All")).clicked() {
+                    current_wheel.push_undo();
+                    current_wheel.restore_all();
+                    *something_changed = true;
                 }
+                if ui.add_enabled(!editing_locked, egui::Button::new("New Wheel From These")).on_hover_text("Copy the removed items into a new wheel").clicked() {
+                    new_wheel_from_removed = Some(false);
+                }
+                if ui.add_enabled(!editing_locked, egui::Button::new("Move to New Wheel")).on_hover_text("Same, but take them off this list").clicked() {
+                    new_wheel_from_removed = Some(true);
+                }
+            });
+        }
 
-                ui.separator();
+        if let Some(take_items) = new_wheel_from_removed {
+            self.new_wheel_from_removed(take_items);
+        }
+    }
 
-                if ui.button("➕ New Wheel").clicked() {
-                    let new_wheel_name = format!("Wheel {}", self.wheels.len() + 1);
-                    self.wheels.push(Wheel::new(new_wheel_name));
-                    self.current = self.wheels.len() - 1;
-                    self.needs_save = true;
+    // collapsible, mostly app wide settings plus a few per wheel looks
+    fn show_settings_section(&mut self, ui: &mut egui::Ui, something_changed: &mut bool) {
+        let mut load_palette = false;
+        let editing_locked = self.editing_locked();
+        let current_wheel = &mut self.wheels[self.current];
+        ui.horizontal(|ui| {
+            ui.heading("Settings");
+            let arrow_symbol = if self.show_settings { "▼" } else { "▶" };
+            if ui.small_button(arrow_symbol).clicked() {
+                self.show_settings = !self.show_settings;
+            }
+        });
+        if self.show_settings {
+            ui.add_enabled_ui(!editing_locked, |ui| {
+                if ui.checkbox(&mut current_wheel.data.heatmap_colors, "Color slices by weight (heatmap)").changed() {
+                    *something_changed = true;
                 }
-                if self.wheels.len() > 1 && ui.button("🗑 Delete Wheel").clicked() {
-                    self.wheels.remove(self.current);
-                    if self.current >= self.wheels.len() {
-                        self.current = self.wheels.len() - 1;
+                ui.horizontal(|ui| {
+                    ui.label("New items start at weight");
+                    let default_weight_box = egui::DragValue::new(&mut current_wheel.data.default_item_weight).range(0..=1000);
+                    if ui.add(default_weight_box).on_hover_text("0 gives new items an equal share of the wheel").changed() {
+                        *something_changed = true;
                     }
-                    self.needs_save = true;
+                });
+                if current_wheel.data.default_item_weight == 0 {
+                    let scale_hint = "Scale every weight up so new items get exactly an equal share";
+                    if ui.checkbox(&mut current_wheel.data.scale_on_add, "Exact share for new items").on_hover_text(scale_hint).changed() {
+                        *something_changed = true;
+                    }
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Slice outline:");
+                    let width_slider = egui::Slider::new(&mut current_wheel.data.slice_stroke_width, 0.0..=6.0).step_by(0.5);
+                    if ui.add(width_slider).on_hover_text("0 for no outline").changed() {
+                        *something_changed = true;
+                    }
+                    if ui.color_edit_button_srgb(&mut current_wheel.data.slice_stroke_color).changed() {
+                        *something_changed = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Center text:");
+                    let mut center_text = current_wheel.data.center_label.clone().unwrap_or_default();
+                    let center_box = egui::TextEdit::singleline(&mut center_text).hint_text("name or emoji").desired_width(100.0);
+                    if ui.add(center_box).changed() {
+                        current_wheel.data.center_label = if center_text.is_empty() { None } else { Some(center_text) };
+                        *something_changed = true;
+                    }
+                });
+                if ui.checkbox(&mut self.settings.animations, "Animations").changed() {
+                    *something_changed = true;
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Recent winners under the wheel:");
+                    if ui.add(egui::DragValue::new(&mut self.settings.recent_winner_chips).range(0..=20)).on_hover_text("0 hides them").changed() {
+                        *something_changed = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let attract_hint = "Slowly turns the wheel when nobody's touched anything for a while, nothing gets picked";
+                    if ui.checkbox(&mut self.settings.attract_mode, "Attract mode after").on_hover_text(attract_hint).changed() {
+                        *something_changed = true;
+                    }
+                    let idle_box = egui::DragValue::new(&mut self.settings.attract_idle_seconds).range(5.0..=3600.0).suffix(" s idle");
+                    if ui.add_enabled(self.settings.attract_mode, idle_box).changed() {
+                        *something_changed = true;
+                    }
+                });
+                let touch_hint = "Bigger buttons and spacing, and tapping the wheel spins it";
+                if ui.checkbox(&mut self.settings.touch_mode, "Touch mode").on_hover_text(touch_hint).changed() {
+                    *something_changed = true;
+                }
+                if ui.checkbox(&mut self.settings.show_removed_inline, "Show removed items in the list").changed() {
+                    *something_changed = true;
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Colours:");
+                    for color_scheme in [ColorScheme::Rainbow, ColorScheme::ColorBlindSafe, ColorScheme::Grayscale] {
+                        if ui.selectable_value(&mut self.settings.color_scheme, color_scheme, color_scheme.label()).changed() {
+                            *something_changed = true;
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Palette:");
+                    match &self.slice_palette {
+                        Some(slice_palette) => {
+                            ui.label(format!("{} ({} colours)", slice_palette.name, slice_palette.colors.len()));
+                            if ui.small_button("Clear").on_hover_text("Back to the built-in colours").clicked() {
+                                self.slice_palette = None;
+                                *something_changed = true;
+                            }
+                        }
+                        None => {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.palette_path_input)
+                                    .hint_text("file of hex colours")
+                                    .desired_width(120.0)
+                            );
+                            if ui.add_enabled(!self.palette_path_input.trim().is_empty(), egui::Button::new("Load palette")).clicked() {
+                                load_palette = true;
+                            }
+                        }
+                    }
+                });
+                if ui.checkbox(&mut self.settings.slice_percentages, "Show % on slices").changed() {
+                    *something_changed = true;
+                }
+                let image_hint = "Stretch item images over their whole slice (slower with lots of images)";
+                if ui.checkbox(&mut self.settings.image_slices, "Fill slices with item images").on_hover_text(image_hint).changed() {
+                    *something_changed = true;
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Unlock PIN:");
+                    ui.add(egui::TextEdit::singleline(&mut self.new_pin_input).password(true).desired_width(60.0));
+                    let pin_label = if self.edit_lock.pin_hash.is_some() { "Change PIN" } else { "Set PIN" };
+                    if ui.add_enabled(!self.new_pin_input.is_empty(), egui::Button::new(pin_label)).clicked() {
+                        self.edit_lock.pin_hash = Some(hash_pin(&self.new_pin_input));
+                        self.new_pin_input.clear();
+                        self.status_message = Some("PIN saved, it's needed to unlock editing".to_string());
+                        *something_changed = true;
+                    }
+                    if self.edit_lock.pin_hash.is_some() && ui.button("Clear PIN").clicked() {
+                        self.edit_lock.pin_hash = None;
+                        *something_changed = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Boost hotkey:");
+                    let bound_item_text = current_wheel.data.boost_hotkey_item.clone().unwrap_or_else(|| "(none)".to_string());
+                    egui::ComboBox::from_id_salt("boost_hotkey_item")
+                        .selected_text(bound_item_text)
+                        .width(100.0)
+                        .show_ui(ui, |ui| {
+                            if ui.selectable_label(current_wheel.data.boost_hotkey_item.is_none(), "(none)").clicked() {
+                                current_wheel.data.boost_hotkey_item = None;
+                                *something_changed = true;
+                            }
+                            for item in &current_wheel.data.items {
+                                let is_bound = current_wheel.data.boost_hotkey_item.as_ref() == Some(&item.name);
+                                if ui.selectable_label(is_bound, &item.name).clicked() {
+                                    current_wheel.data.boost_hotkey_item = Some(item.name.clone());
+                                    *something_changed = true;
+                                }
+                            }
+                        });
+                    let key_text = current_wheel.data.boost_hotkey.clone().unwrap_or_else(|| "(no key)".to_string());
+                    egui::ComboBox::from_id_salt("boost_hotkey")
+                        .selected_text(key_text)
+                        .width(60.0)
+                        .show_ui(ui, |ui| {
+                            for hotkey in BOOST_HOTKEYS {
+                                let is_selected = current_wheel.data.boost_hotkey.as_deref() == Some(hotkey.name());
+                                if ui.selectable_label(is_selected, hotkey.name()).clicked() {
+                                    current_wheel.data.boost_hotkey = Some(hotkey.name().to_string());
+                                    *something_changed = true;
+                                }
+                            }
+                        });
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Spin RNG:");
+                    let mode_before = self.spin_rng.mode;
+                    ui.selectable_value(&mut self.spin_rng.mode, RngMode::Entropy, "Fresh each spin");
+                    ui.selectable_value(&mut self.spin_rng.mode, RngMode::Sequence, "Seeded sequence");
+                    if self.spin_rng.mode != mode_before {
+                        if self.spin_rng.mode == RngMode::Sequence {
+                            self.spin_rng.restart_sequence();
+                        }
+                        *something_changed = true;
+                    }
+                });
+                if self.spin_rng.mode == RngMode::Sequence {
+                    ui.horizontal(|ui| {
+                        ui.label("Base seed:");
+                        if ui.add(egui::DragValue::new(&mut self.spin_rng.base_seed)).changed() {
+                            self.spin_rng.restart_sequence();
+                            *something_changed = true;
+                        }
+                        if ui.small_button("Restart sequence").clicked() {
+                            self.spin_rng.restart_sequence();
+                            *something_changed = true;
+                        }
+                    });
                 }
+                ui.horizontal(|ui| {
+                    ui.label("GIF:");
+                    if ui.add(egui::Slider::new(&mut self.settings.gif_fps, 5..=30).suffix(" fps")).changed() {
+                        *something_changed = true;
+                    }
+                    if ui.add(egui::Slider::new(&mut self.settings.gif_max_size, 120..=600).suffix(" px")).changed() {
+                        *something_changed = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Stack panels below:");
+                    let width_slider = egui::Slider::new(&mut self.settings.stack_below_width, 0.0..=1200.0).suffix(" px");
+                    if ui.add(width_slider).changed() {
+                        *something_changed = true;
+                    }
+                });
             });
-        });
+        }
 
-        let mut something_changed = false;
+        if load_palette {
+            self.load_slice_palette();
+        }
+    }
 
-        egui::SidePanel::left("panel").min_width(260.0).max_width(370.0).show(ctx, |ui| {
-            let current_wheel = &mut self.wheels[self.current];
-            current_wheel.sync_pct_bufs();
+    // collapsible list with copy and export, clearing it asks first
+    fn show_history_section(&mut self, ui: &mut egui::Ui) {
+        let test_mode = self.test_mode;
+        let mut requested_action = None;
+        let editing_locked = self.editing_locked();
+        let current_wheel = &mut self.wheels[self.current];
+        ui.horizontal(|ui| {
+            ui.heading("Winner History");
+            let arrow_symbol = if self.show_history { "▼" } else { "▶" };
+            if ui.small_button(arrow_symbol).clicked() {
+                self.show_history = !self.show_history;
+            }
+        });
 
-            ui.add_space(5.0);
+        let history_is_visible = self.show_history && !current_wheel.data.winner_history.is_empty();
+        if history_is_visible {
+            egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                for (history_index, entry) in current_wheel.data.winner_history.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{}.", history_index + 1));
+                        let text_color = if history_index == 0 {
+                            egui::Color32::from_rgb(255, 215, 0)
+                        } else {
+                            egui::Color32::LIGHT_GRAY
+                        };
+                        ui.label(egui::RichText::new(entry.label()).color(text_color));
+                        if let Some(entry_time) = entry.local_time() {
+                            ui.weak(entry_time);
+                        }
+                    });
+                }
+            });
             ui.horizontal(|ui| {
-                ui.label("Wheel Name:");
-                if ui.text_edit_singleline(&mut current_wheel.data.name).changed() {
-                    something_changed = true;
+                ui.label("Markdown:");
+                ui.selectable_value(&mut self.markdown_table, false, "List");
+                ui.selectable_value(&mut self.markdown_table, true, "Table");
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Copy as Markdown").clicked() {
+                    ui.ctx().copy_text(current_wheel.history_markdown(self.markdown_table));
+                    self.status_message = Some("History copied as Markdown".to_string());
+                }
+                if ui.button("Export Markdown").clicked() {
+                    let history_markdown = current_wheel.history_markdown(self.markdown_table);
+                    self.status_message = Some(match write_export(test_mode, &current_wheel.data.name, "md", &history_markdown) {
+                        Ok(export_path) => format!("History saved to {}", export_path.display()),
+                        Err(err) => format!("Couldn't export history: {}", err),
+                    });
                 }
             });
-
-            ui.add_space(10.0);
-            ui.separator();
-            ui.add_space(10.0);
-
-            ui.heading("Add Items");
             ui.horizontal(|ui| {
-                let text_box_response = ui.text_edit_singleline(&mut current_wheel.state.input_text);
-                let pressed_enter = text_box_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
-                let clicked_add = ui.button("Add").clicked();
-                let has_text = !current_wheel.state.input_text.trim().is_empty();
+                ui.selectable_value(&mut self.history_oldest_first, false, "Newest first");
+                ui.selectable_value(&mut self.history_oldest_first, true, "Oldest first");
+                // cancelling the dialog just gives None, nothing to do then
+                if ui.button("Export History").on_hover_text("Save as a .txt or .csv file").clicked()
+                    && let Some(export_path) = rfd::FileDialog::new()
+                        .add_filter("Text", &["txt"])
+                        .add_filter("CSV", &["csv"])
+                        .set_file_name(format!("{} history.txt", file_safe_name(&current_wheel.data.name)))
+                        .save_file()
+                {
+                    let as_csv = export_path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+                    let history_text = current_wheel.history_export(as_csv, self.history_oldest_first);
+                    self.status_message = Some(match write_app_file(test_mode, &export_path, history_text.as_bytes()) {
+                        Ok(()) => format!("History saved to {}", export_path.display()),
+                        Err(err) => format!("Couldn't save {}: {}", export_path.display(), err),
+                    });
+                }
+            });
+            if ui.add_enabled(!editing_locked, egui::Button::new("Clear History")).clicked() {
+                requested_action = Some(PendingAction::ClearHistory);
+            }
+        }
 
-                if (pressed_enter || clicked_add) && has_text {
-                    let avg_weight = if current_wheel.data.items.is_empty() {
-                        1
-                    } else {
-                        (current_wheel.total_weight() / current_wheel.data.items.len() as u32).max(1)
-                    };
-                    let new_item_name = current_wheel.state.input_text.trim().to_string();
-                    let mut new_item = Item::new(new_item_name);
-                    new_item.weight = avg_weight;
-                    current_wheel.data.items.push(new_item);
-                    for buf in current_wheel.state.pct_bufs.iter_mut() {
-                        buf.clear();
-                    }
-                    current_wheel.state.pct_bufs.push(String::new());
-                    current_wheel.state.input_text.clear();
-                    something_changed = true;
+        if let Some(action) = requested_action {
+            self.request_action(action);
+        }
+    }
+
+    // wins per item against what the weights say to expect
+    fn show_stats_section(&mut self, ui: &mut egui::Ui) {
+        let mut requested_action = None;
+        let editing_locked = self.editing_locked();
+        let current_wheel = &mut self.wheels[self.current];
+        ui.collapsing("Stats", |ui| {
+            let total_wins: u32 = current_wheel.data.items.iter().map(|item| item.win_count).sum();
+            let total_weight = current_wheel.total_weight().max(1);
+            if total_wins == 0 {
+                ui.weak("No wins yet");
+                return;
+            }
+            egui::Grid::new("win_stats").striped(true).show(ui, |ui| {
+                ui.strong("Item");
+                ui.strong("Wins");
+                ui.strong("Observed");
+                ui.strong("Expected");
+                ui.end_row();
+                for item in &current_wheel.data.items {
+                    ui.label(&item.name);
+                    ui.label(item.win_count.to_string());
+                    ui.label(format!("{:.1}%", item.win_count as f32 / total_wins as f32 * 100.0));
+                    ui.label(format!("{:.1}%", item.weight as f32 / total_weight as f32 * 100.0));
+                    ui.end_row();
                 }
             });
+            ui.weak(format!("{} wins across the items on the wheel", total_wins));
+            if ui.add_enabled(!editing_locked, egui::Button::new("Reset Stats")).clicked() {
+                requested_action = Some(PendingAction::ResetStats);
+            }
+        });
 
-            ui.add_space(10.0);
-            ui.heading(format!("Items ({})", current_wheel.data.items.len()));
+        if let Some(action) = requested_action {
+            self.request_action(action);
+        }
+    }
+}
 
-            egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
-                let mut remove_temp: Option<usize> = None;
-                let mut remove_perm: Option<usize> = None;
-                let mut should_commit_edit = false;
-                let mut apply_pct_for_index: Option<usize> = None;
-                let total_weight = current_wheel.total_weight();
+// eframe lol, this is where all of the actual UI is
+impl eframe::App for WheelApp {
+    // flushes any change still waiting on the save delay, and moving or resizing the
+    // window doesn't trigger a save at all so the last geometry gets caught here too
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_data();
+    }
 
-                for item_index in 0..current_wheel.data.items.len() {
-                    let item_pct = current_wheel.data.items[item_index].weight as f32 / total_weight as f32 * 100.0;
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let current_time = std::time::Instant::now();
+        let dt = current_time.duration_since(self.last_time).as_secs_f32();
+        self.last_time = current_time;
 
-                    if current_wheel.state.pct_bufs[item_index].is_empty() {
-                        current_wheel.state.pct_bufs[item_index] = format!("{:.0}", item_pct.round());
-                    }
+        if self.applied_touch_mode != Some(self.settings.touch_mode) {
+            apply_touch_style(ctx, self.settings.touch_mode);
+            self.applied_touch_mode = Some(self.settings.touch_mode);
+        }
+        self.track_window_geometry(ctx);
 
-                    ui.horizontal(|ui| {
-                        let currently_editing_this_item = current_wheel.state.editing_idx == Some(item_index);
-                        if currently_editing_this_item {
-                            let edit_response = ui.add(
-                                egui::TextEdit::singleline(&mut current_wheel.state.edit_buf).desired_width(80.0)
-                            );
-                            let pressed_enter = ui.input(|inp| inp.key_pressed(egui::Key::Enter));
-                            if edit_response.lost_focus() || pressed_enter {
-                                should_commit_edit = true;
-                            }
-                            edit_response.request_focus();
-                        } else {
-                            let item_label = ui.add(
-                                egui::Label::new(&current_wheel.data.items[item_index].name).sense(egui::Sense::click())
-                            );
-                            if item_label.double_clicked() {
-                                current_wheel.state.editing_idx = Some(item_index);
-                                current_wheel.state.edit_buf = current_wheel.data.items[item_index].name.clone();
-                            }
-                            item_label.on_hover_text("Double-click to rename");
-                        }
+        let spin_just_finished = self.advance_current_wheel(dt);
+        self.update_spin_sounds(spin_just_finished);
+        self.update_confetti(ctx, dt);
+        if self.wheels[self.current].advance_replay(dt, &mut self.spin_rng) {
+            ctx.request_repaint();
+        }
+        if let Some(previous_index) = self.previous_wheel {
+            self.switch_timer += dt;
+            if self.switch_timer >= WHEEL_SWITCH_SECONDS || previous_index >= self.wheels.len() {
+                self.previous_wheel = None;
+            }
+            ctx.request_repaint();
+        }
 
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.small_button("🗑").on_hover_text("Delete forever").clicked() {
-                                remove_perm = Some(item_index);
-                            }
-                            if ui.small_button("❌").on_hover_text("Remove temporarily").clicked() {
-                                remove_temp = Some(item_index);
-                            }
+        self.update_gif_recording(ctx, dt);
+        self.update_attract_mode(ctx, dt);
 
-                            ui.label("%");
+        if self.pin_prompt.is_none() && ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::P)) {
+            self.palette.open = !self.palette.open;
+            self.palette.query.clear();
+            self.palette.selected = 0;
+        }
+        self.show_command_palette(ctx);
+        self.show_pin_prompt(ctx);
+        self.show_confirm_prompt(ctx);
+        if !self.modal_open() && ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::D)) {
+            self.decide_current_wheel();
+        }
+        self.show_decide_flash(ctx, dt);
 
-                            let pct_box_response = ui.add(
-                                egui::TextEdit::singleline(&mut current_wheel.state.pct_bufs[item_index])
-                                    .desired_width(36.0)
-                                    .horizontal_align(egui::Align::RIGHT)
-                            );
-                            let pressed_enter = ui.input(|inp| inp.key_pressed(egui::Key::Enter));
-                            if pct_box_response.lost_focus() || pressed_enter {
-                                apply_pct_for_index = Some(item_index);
-                            }
-                            if pct_box_response.gained_focus() {
-                                current_wheel.state.pct_bufs[item_index] = format!("{:.0}", item_pct.round());
+        // live show control, ignored while typing or when a dialog is up
+        let ignore_hotkeys = self.modal_open() || ctx.wants_keyboard_input();
+        let current_wheel = &mut self.wheels[self.current];
+        if !ignore_hotkeys
+            && let Some(hotkey) = current_wheel.data.boost_hotkey.as_deref().and_then(egui::Key::from_name)
+            && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, hotkey))
+            && let Some(bound_name) = current_wheel.data.boost_hotkey_item.clone()
+            && let Some(bound_item) = current_wheel.data.items.iter_mut().find(|item| item.name == bound_name)
+        {
+            bound_item.cycle_boost();
+            self.status_message = Some(format!("⚡ {} boosted ×{} for the next spin", bound_item.name, bound_item.boost));
+        }
+
+        // text boxes keep their own ctrl+z
+        if !ignore_hotkeys && !self.wheels[self.current].state.is_spinning {
+            let (pressed_undo, pressed_redo) = ctx.input_mut(|i| {
+                let pressed_redo = i.consume_key(egui::Modifiers::COMMAND, egui::Key::Y)
+                    || i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z);
+                (i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z), pressed_redo)
+            });
+            if pressed_undo {
+                self.run_command(Command::UndoEdit);
+            }
+            if pressed_redo {
+                self.run_command(Command::RedoEdit);
+            }
+        }
+
+        let current_state = &self.wheels[self.current].state;
+        if current_state.is_spinning || current_state.queue_pause.is_some() {
+            ctx.request_repaint();
+        }
+
+        // background panels are disabled under a modal so tab focus stays inside it
+        let modal_open = self.modal_open();
+
+        egui::TopBottomPanel::top("top").show(ctx, |ui| {
+            if modal_open {
+                ui.disable();
+            }
+            ui.horizontal(|ui| {
+                ui.heading("Gerbil Decide");
+                let can_decide = {
+                    let current_wheel = &self.wheels[self.current];
+                    !current_wheel.state.is_spinning && !current_wheel.data.items.is_empty()
+                };
+                let decide_button = egui::Button::new(egui::RichText::new("⚡ Decide!").size(18.0).strong());
+                if ui.add_enabled(can_decide, decide_button).on_hover_text("Pick right now, no spin (Ctrl+D)").clicked() {
+                    self.decide_current_wheel();
+                }
+                ui.separator();
+
+                let editing_locked = self.edit_lock.locked;
+                let mut switch_to_wheel = None;
+                let mut archive_change = None;
+                for (wheel_index, wheel) in self.wheels.iter().enumerate() {
+                    let is_selected = self.current == wheel_index;
+                    // an archived wheel only shows up here while it's the open one
+                    if wheel.data.archived && !is_selected {
+                        continue;
+                    }
+                    let tab_text = if wheel.data.archived { format!("📦 {}", wheel.data.name) } else { wheel.data.name.clone() };
+                    if ui.selectable_label(is_selected, tab_text).clicked() {
+                        switch_to_wheel = Some(wheel_index);
+                    }
+                }
+
+                let archived_count = self.wheels.iter().filter(|wheel| wheel.data.archived).count();
+                if archived_count > 0 {
+                    ui.menu_button(format!("📦 Archived ({})", archived_count), |ui| {
+                        for (wheel_index, wheel) in self.wheels.iter().enumerate() {
+                            if !wheel.data.archived {
+                                continue;
                             }
-                        });
+                            ui.horizontal(|ui| {
+                                if ui.button(&wheel.data.name).on_hover_text("Open without unarchiving").clicked() {
+                                    switch_to_wheel = Some(wheel_index);
+                                    ui.close_menu();
+                                }
+                                if ui.add_enabled(!editing_locked, egui::Button::new("Unarchive")).clicked() {
+                                    archive_change = Some((wheel_index, false));
+                                    ui.close_menu();
+                                }
+                            });
+                        }
                     });
                 }
+                if let Some(wheel_index) = switch_to_wheel {
+                    self.switch_to_wheel(wheel_index);
+                }
 
-                if let Some(item_index) = apply_pct_for_index {
-                    let did_apply = current_wheel.apply_pct_input(item_index);
-                    if did_apply {
-                        for buf in current_wheel.state.pct_bufs.iter_mut() {
-                            buf.clear();
-                        }
-                        something_changed = true;
+                ui.separator();
+
+                if ui.add_enabled(!editing_locked, egui::Button::new("➕ New Wheel")).clicked() {
+                    self.add_new_wheel();
+                }
+                if ui.add_enabled(!editing_locked, egui::Button::new("⎘ Duplicate Wheel")).on_hover_text("Copy the items and settings, not the history").clicked() {
+                    self.duplicate_current_wheel();
+                }
+                let wheel_locked = self.wheels[self.current].data.locked;
+                let can_merge = !editing_locked && !wheel_locked && !self.wheels[self.current].state.is_spinning;
+                if self.wheels.len() > 1 {
+                    let mut merge_source = None;
+                    ui.add_enabled_ui(can_merge, |ui| {
+                        ui.menu_button("⤵ Merge from…", |ui| {
+                            for (wheel_index, wheel) in self.wheels.iter().enumerate() {
+                                if wheel_index != self.current && ui.button(&wheel.data.name).clicked() {
+                                    merge_source = Some(wheel_index);
+                                    ui.close_menu();
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text("Add copies of another wheel's items to this one");
+                    });
+                    if let Some(source_index) = merge_source {
+                        let source_name = self.wheels[source_index].data.name.clone();
+                        let (added_count, duplicate_count) = self.merge_from_wheel(source_index);
+                        self.status_message = Some(if duplicate_count > 0 {
+                            format!("Added {} items from {}, skipped {} already here", added_count, source_name, duplicate_count)
+                        } else {
+                            format!("Added {} items from {}", added_count, source_name)
+                        });
                     }
                 }
+                if self.wheels.len() > 1 && ui.add_enabled(!editing_locked && !wheel_locked, egui::Button::new("🗑 Delete Wheel")).clicked() {
+                    self.request_action(PendingAction::DeleteWheel(self.current));
+                }
+                if !self.wheels[self.current].data.archived
+                    && ui.add_enabled(!editing_locked, egui::Button::new("📦 Archive")).on_hover_text("Hide this wheel from the tabs").clicked()
+                {
+                    archive_change = Some((self.current, true));
+                }
+                if let Some((wheel_index, archived)) = archive_change {
+                    self.set_archived(wheel_index, archived);
+                }
 
-                if should_commit_edit {
-                    if let Some(editing_index) = current_wheel.state.editing_idx {
-                        let new_name = current_wheel.state.edit_buf.trim().to_string();
-                        if !new_name.is_empty() {
-                            current_wheel.data.items[editing_index].name = new_name;
-                            something_changed = true;
-                        }
-                    }
+                let (wheel_lock_text, wheel_lock_hint) = if wheel_locked {
+                    ("🔒 Wheel", "This wheel can spin but its items can't be changed")
+                } else {
+                    ("🔓 Wheel", "Lock just this wheel's items")
+                };
+                if ui.add_enabled(!editing_locked, egui::Button::new(wheel_lock_text)).on_hover_text(wheel_lock_hint).clicked() {
+                    let current_wheel = &mut self.wheels[self.current];
+                    current_wheel.data.locked = !wheel_locked;
                     current_wheel.state.editing_idx = None;
-                    current_wheel.state.edit_buf.clear();
+                    self.needs_save = true;
                 }
 
-                if let Some(item_index) = remove_perm {
-                    if current_wheel.state.editing_idx == Some(item_index) {
-                        current_wheel.state.editing_idx = None;
+                if !self.test_mode {
+                    let mut switch_to_profile = None;
+                    let mut create_profile = false;
+                    let mut delete_profile = false;
+                    let spinning = self.wheels[self.current].state.is_spinning;
+                    // the edit lock is saved per profile, so switching away would get round it
+                    ui.add_enabled_ui(!spinning && !editing_locked, |ui| {
+                        ui.menu_button(format!("👤 {}", self.profile), |ui| {
+                            for profile in Self::profile_names() {
+                                if ui.selectable_label(profile == self.profile, &profile).clicked() {
+                                    switch_to_profile = Some(profile);
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            ui.horizontal(|ui| {
+                                let name_response = ui.add(egui::TextEdit::singleline(&mut self.new_profile_input).hint_text("new profile").desired_width(100.0));
+                                let pressed_enter = name_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                if ui.button("Create").clicked() || pressed_enter {
+                                    create_profile = true;
+                                    ui.close_menu();
+                                }
+                            });
+                            if ui.add_enabled(self.profile != DEFAULT_PROFILE, egui::Button::new("🗑 Delete This Profile")).clicked() {
+                                delete_profile = true;
+                                ui.close_menu();
+                            }
+                        })
+                        .response
+                        .on_hover_text("Profiles each keep their own wheels and settings");
+                    });
+                    if let Some(profile) = switch_to_profile {
+                        self.switch_profile(profile);
                     }
-                    current_wheel.data.items.remove(item_index);
-                    current_wheel.state.pct_bufs.remove(item_index);
-                    for buf in current_wheel.state.pct_bufs.iter_mut() {
-                        buf.clear();
+                    if create_profile {
+                        let typed_name = std::mem::take(&mut self.new_profile_input);
+                        self.status_message = Some(match self.create_profile(&typed_name) {
+                            Ok(profile) => format!("Created profile {}", profile),
+                            Err(err) => err,
+                        });
+                    }
+                    if delete_profile {
+                        self.request_action(PendingAction::DeleteProfile(self.profile.clone()));
                     }
-                    something_changed = true;
                 }
-                if let Some(item_index) = remove_temp {
-                    if current_wheel.state.editing_idx == Some(item_index) {
-                        current_wheel.state.editing_idx = None;
+                ui.menu_button("⋯", |ui| {
+                    if ui.button("Export All…").on_hover_text("Save every wheel to one file").clicked() {
+                        self.export_all();
+                        ui.close_menu();
                     }
-                    let moved_item = current_wheel.data.items.remove(item_index);
-                    current_wheel.state.pct_bufs.remove(item_index);
-                    for buf in current_wheel.state.pct_bufs.iter_mut() {
-                        buf.clear();
+                    let can_import = !editing_locked && !self.wheels[self.current].state.is_spinning;
+                    if ui.add_enabled(can_import, egui::Button::new("Import All…")).on_hover_text("Replace every wheel with the ones in an exported file").clicked() {
+                        self.import_all();
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    let any_history = self.wheels.iter().any(|wheel| !wheel.data.winner_history.is_empty());
+                    if ui.add_enabled(!editing_locked && any_history, egui::Button::new("Clear All History…")).clicked() {
+                        self.request_action(PendingAction::ClearAllHistory);
+                        ui.close_menu();
+                    }
+                });
+
+                ui.separator();
+                let (sound_text, sound_hint) = if self.settings.muted { ("🔇", "Sound off") } else { ("🔊", "Sound on") };
+                if ui.button(sound_text).on_hover_text(sound_hint).clicked() {
+                    self.set_muted(!self.settings.muted);
+                }
+                let lock_text = if editing_locked { "🔒 Unlock Editing" } else { "🔓 Lock Editing" };
+                if ui.button(lock_text).clicked() {
+                    if !editing_locked {
+                        self.edit_lock.locked = true;
+                        self.needs_save = true;
+                    } else if self.edit_lock.pin_hash.is_some() {
+                        self.pin_prompt = Some(PinPrompt::default());
+                    } else {
+                        self.edit_lock.locked = false;
+                        self.needs_save = true;
                     }
-                    current_wheel.data.removed_items.push(moved_item);
-                    something_changed = true;
                 }
             });
+        });
 
-            ui.add_space(10.0);
+        egui::TopBottomPanel::bottom("status").show(ctx, |ui| {
+            if modal_open {
+                ui.disable();
+            }
             ui.horizontal(|ui| {
-                let wheel_has_enough_items = current_wheel.data.items.len() >= 2;
-                let can_spin = !current_wheel.state.is_spinning && wheel_has_enough_items;
-                if ui.add_enabled(can_spin, egui::Button::new("🎲 SPIN!")).clicked() {
-                    current_wheel.spin();
-                }
-                if ui.button("Clear All").clicked() {
-                    current_wheel.data.items.clear();
-                    current_wheel.data.winner_history.clear();
-                    current_wheel.state.pct_bufs.clear();
-                    current_wheel.state.editing_idx = None;
-                    something_changed = true;
+                match &self.status_message {
+                    Some(message) => ui.label(message),
+                    None => ui.weak("Ready"),
+                };
+                if self.status_message.is_some() && ui.small_button("✖").clicked() {
+                    self.status_message = None;
                 }
             });
+        });
 
-            ui.add_space(5.0);
-            if ui.checkbox(&mut current_wheel.data.remove_winner, "Remove winner after spin").changed() {
-                something_changed = true;
-            }
-            if ui.checkbox(&mut current_wheel.data.auto_spin, "Keep spinning until one left").changed() {
-                something_changed = true;
-            }
-
-            ui.add_space(5.0);
+        let mut something_changed = false;
 
-            if !current_wheel.data.removed_items.is_empty() {
-                ui.separator();
-                ui.add_space(5.0);
+        let screen_width = ctx.screen_rect().width();
+        if screen_width < self.settings.stack_below_width {
+            egui::TopBottomPanel::top("drawer").show(ctx, |ui| {
+                if modal_open {
+                    ui.disable();
+                }
                 ui.horizontal(|ui| {
-                    ui.heading(format!("Removed ({})", current_wheel.data.removed_items.len()));
-                    let arrow_symbol = if self.show_removed { "▼" } else { "▶" };
+                    let arrow_symbol = if self.show_drawer { "▼" } else { "▶" };
                     if ui.small_button(arrow_symbol).clicked() {
-                        self.show_removed = !self.show_removed;
+                        self.show_drawer = !self.show_drawer;
                     }
+                    ui.label(format!("Items & Settings ({} items)", self.wheels[self.current].data.items.len()));
                 });
-                if self.show_removed {
-                    egui::ScrollArea::vertical().max_height(100.0).show(ui, |ui| {
-                        for removed_item in &current_wheel.data.removed_items {
-                            ui.label(&removed_item.name);
-                        }
+                if self.show_drawer {
+                    let drawer_height = ctx.screen_rect().height() * 0.5;
+                    egui::ScrollArea::vertical().id_salt("drawer").max_height(drawer_height).show(ui, |ui| {
+                        self.show_controls(ui, &mut something_changed);
                     });
                 }
-                if ui.button("Restore All").clicked() {
-                    let how_many_removed = current_wheel.data.removed_items.len();
-                    current_wheel.data.items.append(&mut current_wheel.data.removed_items);
-                    for _ in 0..how_many_removed {
-                        current_wheel.state.pct_bufs.push(String::new());
-                    }
-                    for buf in current_wheel.state.pct_bufs.iter_mut() {
-                        buf.clear();
-                    }
-                    something_changed = true;
-                }
-            }
-
-            ui.add_space(10.0);
-            ui.separator();
-            ui.add_space(10.0);
-
-            ui.horizontal(|ui| {
-                ui.heading("Winner History");
-                let arrow_symbol = if self.show_history { "▼" } else { "▶" };
-                if ui.small_button(arrow_symbol).clicked() {
-                    self.show_history = !self.show_history;
+            });
+        } else {
+            egui::SidePanel::left("panel").min_width(260.0).max_width(370.0).show(ctx, |ui| {
+                if modal_open {
+                    ui.disable();
                 }
+                self.show_controls(ui, &mut something_changed);
             });
+        }
 
-            let history_is_visible = self.show_history && !current_wheel.data.winner_history.is_empty();
-            if history_is_visible {
-                egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
-                    for (history_index, winner_name) in current_wheel.data.winner_history.iter().enumerate() {
-                        ui.horizontal(|ui| {
-                            ui.label(format!("{}.", history_index + 1));
-                            let text_color = if history_index == 0 {
-                                egui::Color32::from_rgb(255, 215, 0)
-                            } else {
-                                egui::Color32::LIGHT_GRAY
-                            };
-                            ui.label(egui::RichText::new(winner_name).color(text_color));
-                        });
-                    }
-                });
-                if ui.button("Clear History").clicked() {
-                    current_wheel.data.winner_history.clear();
-                    something_changed = true;
-                }
-            }
-        });
+        if something_changed {
+            self.wheels[self.current].state.cleared_stash = None;
+        }
 
         if something_changed || self.needs_save {
-            self.save_data();
+            self.last_change = Some(current_time);
             self.needs_save = false;
         }
+        if let Some(last_change) = self.last_change {
+            let waited = current_time.duration_since(last_change).as_secs_f32();
+            if waited >= SAVE_DELAY_SECONDS {
+                self.save_data();
+                self.last_change = None;
+            } else {
+                // nothing may repaint while idle, so wake up when the save is due
+                ctx.request_repaint_after(std::time::Duration::from_secs_f32(SAVE_DELAY_SECONDS - waited));
+            }
+        }
 
         egui::CentralPanel::default().show(ctx, |ui| {
-            let current_wheel = &self.wheels[self.current];
+            if modal_open {
+                ui.disable();
+            }
+            // fade the old wheel out for the first half of a switch, then the new one in
+            let mut displayed_index = self.current;
+            if let Some(previous_index) = self.previous_wheel {
+                let halfway = WHEEL_SWITCH_SECONDS / 2.0;
+                if self.switch_timer < halfway {
+                    displayed_index = previous_index;
+                    ui.set_opacity(1.0 - self.switch_timer / halfway);
+                } else {
+                    ui.set_opacity((self.switch_timer - halfway) / halfway);
+                }
+            }
+            let current_wheel = &self.wheels[displayed_index];
+            let mut toggle_pin = false;
+            let mut spin_tapped = false;
+            let mut provisional_choice = None;
+
+            if let Some((_, provisional_name)) = &current_wheel.state.provisional_winner {
+                ui.add_space(10.0);
+                ui.vertical_centered(|ui| {
+                    ui.heading("❓ Provisional Result:");
+                    ui.label(
+                        egui::RichText::new(provisional_name)
+                            .size(36.0)
+                            .italics()
+                            .color(egui::Color32::from_gray(190)),
+                    );
+                    ui.horizontal(|ui| {
+                        if ui.button("✔ Accept").clicked() {
+                            provisional_choice = Some(true);
+                        }
+                        if ui.button("✖ Reject and respin").clicked() {
+                            provisional_choice = Some(false);
+                        }
+                    });
+                });
+                ui.add_space(10.0);
+            }
 
-            if !current_wheel.data.winner_history.is_empty() {
+            let pinned_winner = current_wheel.state.pinned_winner.as_ref();
+            let banner_winner = pinned_winner.or(current_wheel.data.winner_history.first().map(|entry| &entry.name));
+            if let Some(banner_winner_name) = banner_winner {
                 ui.add_space(10.0);
                 ui.vertical_centered(|ui| {
-                    ui.heading("🎉 Latest Winner:");
-                    let latest_winner_name = &current_wheel.data.winner_history[0];
+                    let heading_text = if pinned_winner.is_some() { "📌 Pinned Winner:" } else { "🎉 Latest Winner:" };
+                    ui.heading(heading_text);
                     ui.label(
-                        egui::RichText::new(latest_winner_name)
+                        egui::RichText::new(banner_winner_name)
                             .size(36.0)
                             .color(egui::Color32::from_rgb(255, 215, 0)),
                     );
+                    ui.horizontal(|ui| {
+                        let pin_button_text = if pinned_winner.is_some() { "Unpin" } else { "📌 Pin" };
+                        if ui.small_button(pin_button_text).on_hover_text("Keep this result on screen").clicked() {
+                            toggle_pin = true;
+                        }
+                        if ui.small_button("📋 Copy").on_hover_text("Copy the winner to the clipboard").clicked() {
+                            ui.ctx().copy_text(banner_winner_name.clone());
+                            self.status_message = Some(format!("Copied \"{}\"", banner_winner_name));
+                        }
+                    });
                 });
                 ui.add_space(10.0);
             }
@@ -609,13 +5567,21 @@ impl eframe::App for WheelApp {
             ui.separator();
             ui.add_space(20.0);
             // wheel graphics below, i'm not using a png
+            let show_chips = self.settings.recent_winner_chips > 0 && !current_wheel.data.winner_history.is_empty();
             if !current_wheel.data.items.is_empty() {
-                let available_space = ui.available_size();
+                let mut available_space = ui.available_size();
+                if show_chips {
+                    available_space.y -= 40.0;
+                }
                 let wheel_size = (available_space.y.min(available_space.x) * 0.85).max(200.0);
-                let total_weight = current_wheel.total_weight() as f32;
 
                 ui.vertical_centered(|ui| {
-                    let (_id, wheel_rect) = ui.allocate_space(egui::vec2(wheel_size, wheel_size));
+                    let wheel_sense = if self.settings.touch_mode { egui::Sense::click() } else { egui::Sense::hover() };
+                    let (wheel_rect, wheel_response) = ui.allocate_exact_size(egui::vec2(wheel_size, wheel_size), wheel_sense);
+                    self.wheel_rect = Some(wheel_rect);
+                    if wheel_response.clicked() {
+                        spin_tapped = true;
+                    }
 
                     if ui.is_rect_visible(wheel_rect) {
                         let painter = ui.painter();
@@ -624,8 +5590,8 @@ impl eframe::App for WheelApp {
 
                         if current_wheel.data.items.len() == 1 {
                             painter.circle_filled(wheel_center, wheel_radius, egui::Color32::from_rgb(100, 150, 200));
-                            painter.circle_stroke(wheel_center, wheel_radius, egui::Stroke::new(2.0, egui::Color32::WHITE));
-                            let font_size = (wheel_size / 25.0).max(12.0).min(18.0);
+                            painter.circle_stroke(wheel_center, wheel_radius, current_wheel.data.slice_stroke());
+                            let font_size = (wheel_size / 25.0).clamp(12.0, 18.0);
                             painter.text(
                                 egui::pos2(wheel_center.x, wheel_center.y - wheel_radius * 0.3),
                                 egui::Align2::CENTER_CENTER,
@@ -634,17 +5600,14 @@ impl eframe::App for WheelApp {
                                 egui::Color32::WHITE,
                             );
                         } else {
-                            let mut current_angle = -current_wheel.state.rotation;
-                            for (item_index, item) in current_wheel.data.items.iter().enumerate() {
-                                let slice_angle = 2.0 * PI * (item.weight as f32 / total_weight);
-                                let slice_start_angle = current_angle;
-                                let slice_end_angle = current_angle + slice_angle;
-
-                                let hue = item_index as f32 / current_wheel.data.items.len() as f32;
-                                let red_amount = (255.0 * (hue * 6.0).sin().abs()) as u8;
-                                let green_amount = (255.0 * ((hue * 6.0) + 2.0).sin().abs()) as u8;
-                                let blue_amount = (255.0 * ((hue * 6.0) + 4.0).sin().abs()) as u8;
-                                let slice_color = egui::Color32::from_rgb(red_amount, green_amount, blue_amount);
+                            let slice_spans = current_wheel.slice_spans(&current_wheel.display_weights());
+                            for &(item_index, span_start, span_end) in &slice_spans {
+                                let item = &current_wheel.data.items[item_index];
+                                let slice_angle = span_end - span_start;
+                                let slice_start_angle = span_start - current_wheel.state.rotation;
+                                let slice_end_angle = span_end - current_wheel.state.rotation;
+
+                                let slice_color = current_wheel.slice_color(item_index, self.slice_palette.as_ref(), self.settings.color_scheme);
 
                                 let mut slice_points = vec![wheel_center];
                                 for step in 0..=30 {
@@ -654,48 +5617,753 @@ impl eframe::App for WheelApp {
                                     slice_points.push(egui::pos2(point_x, point_y));
                                 }
 
-                                painter.add(egui::Shape::convex_polygon(
-                                    slice_points,
-                                    slice_color,
-                                    egui::Stroke::new(2.0, egui::Color32::WHITE),
-                                ));
-
                                 let label_angle = (slice_start_angle + slice_end_angle) / 2.0;
+                                let item_texture = item.image_path.as_ref().and_then(|image_path| {
+                                    item_texture(ui.ctx(), &mut self.image_cache, &mut self.status_message, image_path)
+                                });
+                                match item_texture {
+                                    Some(texture_id) if self.settings.image_slices => {
+                                        painter.add(image_wedge_mesh(texture_id, &slice_points, wheel_center, label_angle));
+                                        painter.add(egui::Shape::closed_line(slice_points, current_wheel.data.slice_stroke()));
+                                    }
+                                    _ => {
+                                        painter.add(egui::Shape::convex_polygon(
+                                            slice_points,
+                                            slice_color,
+                                            current_wheel.data.slice_stroke(),
+                                        ));
+                                    }
+                                }
+
                                 let label_radius = wheel_radius * 0.7;
                                 let label_x = wheel_center.x + label_radius * label_angle.cos();
                                 let label_y = wheel_center.y + label_radius * label_angle.sin();
-                                let font_size = (wheel_size / 25.0).max(12.0).min(18.0);
+                                if let Some(texture_id) = item_texture
+                                    && !self.settings.image_slices
+                                {
+                                    let icon_size = (wheel_size / 14.0).clamp(16.0, 40.0);
+                                    let icon_radius = wheel_radius * 0.45;
+                                    let icon_center = egui::pos2(
+                                        wheel_center.x + icon_radius * label_angle.cos(),
+                                        wheel_center.y + icon_radius * label_angle.sin(),
+                                    );
+                                    let icon_rect = egui::Rect::from_center_size(icon_center, egui::vec2(icon_size, icon_size));
+                                    let full_uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                                    painter.image(texture_id, icon_rect, full_uv, egui::Color32::WHITE);
+                                }
+                                // the labels sit flat, so the arc across the slice at the label is about the room there is
+                                let label_room = (slice_angle * label_radius).min(wheel_radius * 0.8);
+                                let (label_text, font_size) = fit_slice_label(painter, &item.name, (wheel_size / 25.0).clamp(12.0, 18.0), label_room);
                                 painter.text(
                                     egui::pos2(label_x, label_y),
                                     egui::Align2::CENTER_CENTER,
-                                    &item.name,
+                                    label_text,
                                     egui::FontId::proportional(font_size),
                                     egui::Color32::WHITE,
                                 );
+                                if self.settings.slice_percentages {
+                                    let slice_pct = slice_angle / (2.0 * PI) * 100.0;
+                                    // dark text on light slices, with a shadow in the other colour so it reads on images too
+                                    let slice_rgb = [slice_color.r(), slice_color.g(), slice_color.b()];
+                                    let light_slice = !self.settings.image_slices && contrast_with_white(slice_rgb) < MIN_LABEL_CONTRAST;
+                                    let (pct_color, shadow_color) = if light_slice {
+                                        (egui::Color32::BLACK, egui::Color32::from_white_alpha(160))
+                                    } else {
+                                        (egui::Color32::WHITE, egui::Color32::from_black_alpha(160))
+                                    };
+                                    let pct_position = egui::pos2(label_x, label_y + font_size);
+                                    let pct_text = format!("{:.0}%", slice_pct);
+                                    let pct_font = egui::FontId::proportional(font_size * 0.8);
+                                    painter.text(pct_position + egui::vec2(1.0, 1.0), egui::Align2::CENTER_CENTER, &pct_text, pct_font.clone(), shadow_color);
+                                    painter.text(pct_position, egui::Align2::CENTER_CENTER, pct_text, pct_font, pct_color);
+                                }
 
-                                current_angle = slice_end_angle;
+                            }
+
+                            // afterimages at the last couple of angles while it's going fast, they
+                            // fade out as the wheel slows down
+                            let trail_strength = (current_wheel.state.velocity / 0.6).min(1.0);
+                            if self.settings.animations && current_wheel.state.is_spinning && trail_strength > 0.1 {
+                                for trail_step in 1..=TRAIL_COPIES {
+                                    let trail_offset = current_wheel.state.velocity * trail_step as f32 * 0.5 * current_wheel.data.spin_direction();
+                                    let trail_color_scale = 0.3 * trail_strength / trail_step as f32;
+                                    for &(item_index, span_start, span_end) in &slice_spans {
+                                        let trail_angle = span_start - current_wheel.state.rotation + trail_offset;
+                                        let slice_angle = span_end - span_start;
+                                        let mut trail_points = vec![wheel_center];
+                                        for step in 0..=12 {
+                                            let angle_at_step = trail_angle + slice_angle * step as f32 / 12.0;
+                                            trail_points.push(wheel_center + wheel_radius * egui::vec2(angle_at_step.cos(), angle_at_step.sin()));
+                                        }
+                                        let trail_color = current_wheel.slice_color(item_index, self.slice_palette.as_ref(), self.settings.color_scheme).gamma_multiply(trail_color_scale);
+                                        painter.add(egui::Shape::convex_polygon(trail_points, trail_color, egui::Stroke::NONE));
+                                    }
+                                }
                             }
                         }
 
-                        let center_dot_size = (wheel_size / 20.0).max(10.0);
+                        let center_label = current_wheel.data.center_label.as_deref().map(str::trim).filter(|label| !label.is_empty());
+                        // a labelled hub gets a bit bigger so the text has room
+                        let center_dot_size = if center_label.is_some() { (wheel_size / 10.0).max(20.0) } else { (wheel_size / 20.0).max(10.0) };
                         painter.circle_filled(wheel_center, center_dot_size, egui::Color32::from_rgb(50, 50, 50));
+                        if let Some(center_text) = center_label {
+                            // rough fit, proportional glyphs are a bit over half as wide as they are tall
+                            let text_chars = center_text.chars().count().max(1) as f32;
+                            let center_font_size = (center_dot_size * 1.7 / (text_chars * 0.6)).min(center_dot_size);
+                            painter.text(
+                                wheel_center,
+                                egui::Align2::CENTER_CENTER,
+                                center_text,
+                                egui::FontId::proportional(center_font_size),
+                                egui::Color32::WHITE,
+                            );
+                        }
 
+                        // worked out pointing outwards along pointer_pos so any edge uses the same shape
                         let arrow_size = wheel_size / 50.0;
-                        let arrow_tip_y = wheel_rect.top() + arrow_size * 2.5;
-                        let arrow_left_x = wheel_center.x - arrow_size;
-                        let arrow_right_x = wheel_center.x + arrow_size;
+                        let pointer_angle = current_wheel.data.pointer_pos.angle();
+                        let outwards = egui::vec2(pointer_angle.cos(), pointer_angle.sin());
+                        let sideways = outwards.rot90();
+                        let arrow_base = wheel_center + outwards * (wheel_size / 2.0 - 5.0);
+                        let arrow_tip = wheel_center + outwards * (wheel_size / 2.0 - arrow_size * 2.5);
                         painter.add(egui::Shape::convex_polygon(
-                            vec![
-                                egui::pos2(wheel_center.x, arrow_tip_y),
-                                egui::pos2(arrow_left_x, wheel_rect.top() + 5.0),
-                                egui::pos2(arrow_right_x, wheel_rect.top() + 5.0),
-                            ],
+                            vec![arrow_tip, arrow_base - sideways * arrow_size, arrow_base + sideways * arrow_size],
                             egui::Color32::RED,
                             egui::Stroke::new(2.0, egui::Color32::DARK_RED),
                         ));
+
+                        let confetti_opacity = 1.0 - self.confetti_age / CONFETTI_SECONDS;
+                        for particle in &self.confetti {
+                            painter.circle_filled(wheel_center + particle.offset, 4.0, particle.color.gamma_multiply(confetti_opacity));
+                        }
+
+                        // notes pop up over their slice, only once the wheel has stopped
+                        if !current_wheel.state.is_spinning
+                            && let Some(hover_pos) = wheel_response.hover_pos()
+                            && (hover_pos - wheel_center).length() <= wheel_radius
+                        {
+                            let from_center = hover_pos - wheel_center;
+                            let hover_spans = current_wheel.slice_spans(&current_wheel.display_weights());
+                            let hovered_index = current_wheel.item_at_angle(from_center.y.atan2(from_center.x), &hover_spans);
+                            if let Some(hovered_item) = current_wheel.data.items.get(hovered_index)
+                                && !hovered_item.notes.trim().is_empty()
+                            {
+                                wheel_response.on_hover_text(hovered_item.notes.trim());
+                            }
+                        }
+                    }
+                });
+            }
+
+            // quick look at the last few results, the full list is in the history section
+            if show_chips {
+                ui.add_space(8.0);
+                ui.horizontal_wrapped(|ui| {
+                    ui.weak("Recent:");
+                    for winner_name in current_wheel.data.winner_history.iter().take(self.settings.recent_winner_chips).map(|entry| &entry.name) {
+                        // removed winners have no slice any more, so they get a plain grey chip
+                        let chip_color = match current_wheel.data.items.iter().position(|item| &item.name == winner_name) {
+                            Some(item_index) => current_wheel.slice_color(item_index, self.slice_palette.as_ref(), self.settings.color_scheme),
+                            None => egui::Color32::from_gray(90),
+                        };
+                        egui::Frame::none()
+                            .fill(chip_color)
+                            .rounding(8.0)
+                            .inner_margin(egui::Margin::symmetric(8.0, 3.0))
+                            .show(ui, |ui| {
+                                ui.label(egui::RichText::new(winner_name).color(egui::Color32::WHITE));
+                            });
                     }
                 });
             }
+
+            if let Some(accepted) = provisional_choice {
+                self.wheels[displayed_index].resolve_provisional(accepted, &mut self.spin_rng);
+                self.needs_save = true;
+                if accepted && displayed_index == self.current {
+                    self.maybe_spawn_confetti();
+                }
+            }
+            if spin_tapped && displayed_index == self.current && self.wheels[self.current].can_spin() {
+                self.wheels[self.current].spin(&mut self.spin_rng);
+            }
+            if toggle_pin {
+                let displayed_wheel = &mut self.wheels[displayed_index];
+                if displayed_wheel.state.pinned_winner.is_some() {
+                    displayed_wheel.state.pinned_winner = None;
+                } else {
+                    displayed_wheel.state.pinned_winner = displayed_wheel.data.winner_history.first().map(|entry| entry.name.clone());
+                }
+            }
         });
+
+        if modal_open {
+            let dim_painter = ctx.layer_painter(egui::LayerId::background());
+            dim_painter.rect_filled(ctx.screen_rect(), 0.0, egui::Color32::from_black_alpha(120));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pct_accepts_plain_and_percent_sign() {
+        assert_eq!(parse_pct("25"), Some(25.0));
+        assert_eq!(parse_pct("25%"), Some(25.0));
+        assert_eq!(parse_pct(" 25 % "), Some(25.0));
+        assert_eq!(parse_pct("%25"), Some(25.0));
+        assert_eq!(parse_pct("12.5"), Some(12.5));
+        assert_eq!(parse_pct("1,5"), Some(1.5));
+    }
+
+    #[test]
+    fn parse_pct_rejects_junk() {
+        assert_eq!(parse_pct(""), None);
+        assert_eq!(parse_pct("   "), None);
+        assert_eq!(parse_pct("%"), None);
+        assert_eq!(parse_pct("abc"), None);
+        assert_eq!(parse_pct("1.2.3"), None);
+        assert_eq!(parse_pct("inf"), None);
+        assert_eq!(parse_pct("NaN"), None);
+    }
+
+    fn wheel_data_from(items_json: &str) -> WheelData {
+        serde_json::from_str(&format!("{{\"name\": \"Test\", \"items\": {}}}", items_json)).expect("wheel data should load")
+    }
+
+    #[test]
+    fn items_load_from_plain_names() {
+        let wheel_data = wheel_data_from(r#"["a", "b"]"#);
+        let names: Vec<&str> = wheel_data.items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert!(wheel_data.items.iter().all(|item| item.weight == 1));
+    }
+
+    #[test]
+    fn items_load_from_full_items() {
+        let wheel_data = wheel_data_from(r#"[{"name": "a", "weight": 3}]"#);
+        assert_eq!(wheel_data.items.len(), 1);
+        assert_eq!(wheel_data.items[0].name, "a");
+        assert_eq!(wheel_data.items[0].weight, 3);
+    }
+
+    #[test]
+    fn items_load_from_a_mixed_list() {
+        let wheel_data = wheel_data_from(r#"["a", {"name": "b", "weight": 5}, "c"]"#);
+        let loaded: Vec<(&str, u32)> = wheel_data.items.iter().map(|item| (item.name.as_str(), item.weight)).collect();
+        assert_eq!(loaded, [("a", 1), ("b", 5), ("c", 1)]);
+    }
+
+    fn wheel_with_weights(weights: &[u32]) -> Wheel {
+        let mut wheel = Wheel::new("Test".to_string());
+        wheel.data.items.clear();
+        wheel.state.pct_bufs.clear();
+        for (item_index, &weight) in weights.iter().enumerate() {
+            wheel.add_item_with_weight(format!("item {}", item_index), weight);
+        }
+        wheel
+    }
+
+    // share of the wheel the last item got, as a fraction
+    fn share_of_last_item(wheel: &Wheel) -> f64 {
+        let total: u32 = wheel.data.items.iter().map(|item| item.weight).sum();
+        wheel.data.items.last().unwrap().weight as f64 / total as f64
+    }
+
+    #[test]
+    fn new_item_share_on_small_weights_rounded() {
+        let mut single_wheel = wheel_with_weights(&[1]);
+        single_wheel.add_item("new".to_string());
+        assert_eq!(single_wheel.data.items[1].weight, 1);
+        assert_eq!(share_of_last_item(&single_wheel), 0.5);
+
+        // average of 1.5 rounds up to 2, so a bit more than a third
+        let mut pair_wheel = wheel_with_weights(&[1, 2]);
+        pair_wheel.add_item("new".to_string());
+        assert_eq!(pair_wheel.data.items[2].weight, 2);
+        assert_eq!(share_of_last_item(&pair_wheel), 0.4);
+    }
+
+    #[test]
+    fn new_item_share_on_small_weights_scaled() {
+        let mut single_wheel = wheel_with_weights(&[1]);
+        single_wheel.data.scale_on_add = true;
+        single_wheel.add_item("new".to_string());
+        assert_eq!(share_of_last_item(&single_wheel), 0.5);
+
+        // everything doubles so the new item can get exactly the average
+        let mut pair_wheel = wheel_with_weights(&[1, 2]);
+        pair_wheel.data.scale_on_add = true;
+        pair_wheel.add_item("new".to_string());
+        let weights: Vec<u32> = pair_wheel.data.items.iter().map(|item| item.weight).collect();
+        assert_eq!(weights, [2, 4, 3]);
+        assert!((share_of_last_item(&pair_wheel) - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bulk_add_scales_once_for_the_whole_paste() {
+        let mut wheel = wheel_with_weights(&[1, 2]);
+        wheel.data.scale_on_add = true;
+        assert_eq!(wheel.add_items_bulk("a\nb\nheavy, 9\nc"), (4, 0));
+        let weights: Vec<u32> = wheel.data.items.iter().map(|item| item.weight).collect();
+        assert_eq!(weights, [2, 4, 3, 3, 9, 3]);
+    }
+
+    #[test]
+    fn only_non_zero_item_always_wins() {
+        let wheel = wheel_with_weights(&[0, 0, 4, 0]);
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..200 {
+            assert_eq!(wheel.pick_winner(&mut rng, None), Some(2));
+        }
+    }
+
+    #[test]
+    fn only_non_zero_item_gets_the_whole_circle() {
+        let wheel = wheel_with_weights(&[0, 3, 0]);
+        let spans = wheel.slice_spans(&wheel.draw_weights());
+        assert_eq!(spans.len(), 1);
+        let (item_index, span_start, span_end) = spans[0];
+        assert_eq!(item_index, 1);
+        assert_eq!(span_start, 0.0);
+        assert!((span_end - 2.0 * PI).abs() < 1e-5);
+    }
+
+    fn weights_of(wheel: &Wheel) -> Vec<u32> {
+        wheel.data.items.iter().map(|item| item.weight).collect()
+    }
+
+    #[test]
+    fn balanced_percentages_add_up_to_100() {
+        let mut even_wheel = wheel_with_weights(&[1, 1, 1]);
+        assert!(even_wheel.balance_percentages());
+        assert_eq!(weights_of(&even_wheel), [34, 33, 33]);
+        assert_eq!(even_wheel.shown_pct_total(), 100);
+
+        let mut uneven_wheel = wheel_with_weights(&[2, 3, 7]);
+        assert!(uneven_wheel.balance_percentages());
+        assert_eq!(weights_of(&uneven_wheel), [16, 25, 59]);
+        assert_eq!(uneven_wheel.shown_pct_total(), 100);
+    }
+
+    #[test]
+    fn balancing_keeps_zero_weights_and_the_1_percent_floor() {
+        let mut wheel = wheel_with_weights(&[0, 1, 400]);
+        assert!(wheel.balance_percentages());
+        assert_eq!(weights_of(&wheel), [0, 1, 99]);
+        assert_eq!(wheel.shown_pct_total(), 100);
+        // already balanced, so a second go changes nothing
+        assert!(!wheel.balance_percentages());
+    }
+
+    #[test]
+    fn bulk_add_skips_blank_lines_and_reads_weights() {
+        let mut wheel = wheel_with_weights(&[]);
+        let (added_count, duplicate_count) = wheel.add_items_bulk("pizza, 3\n\n   \n\ttacos (2d6)\nsushi\n");
+        assert_eq!((added_count, duplicate_count), (3, 0));
+        let loaded: Vec<(&str, u32)> = wheel.data.items.iter().map(|item| (item.name.as_str(), item.weight)).collect();
+        assert_eq!(loaded[0], ("pizza", 3));
+        assert_eq!(loaded[1], ("tacos", 7));
+        assert_eq!(loaded[2].0, "sushi");
+        assert_eq!(wheel.state.pct_bufs.len(), 3);
+    }
+
+    #[test]
+    fn bulk_add_counts_skipped_duplicates() {
+        let mut wheel = wheel_with_weights(&[]);
+        wheel.add_item_with_weight("Pizza".to_string(), 1);
+        // "pizza" is already there in another case, and tacos shows up twice in the paste
+        let (added_count, duplicate_count) = wheel.add_items_bulk("pizza, 2\ntacos\nTACOS (3)\nsushi");
+        assert_eq!((added_count, duplicate_count), (2, 2));
+        let names: Vec<&str> = wheel.data.items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["Pizza", "tacos", "sushi"]);
+
+        wheel.data.allow_duplicates = true;
+        assert_eq!(wheel.add_items_bulk("pizza\npizza"), (2, 0));
+        assert_eq!(wheel.data.items.len(), 5);
+    }
+
+    #[test]
+    fn applying_a_preset_can_be_undone() {
+        let mut wheel = wheel_with_weights(&[1, 2, 3]);
+        wheel.data.weight_presets.push(("flat".to_string(), vec![5, 5, 5]));
+        wheel.apply_weight_preset("missing");
+        assert!(wheel.state.undo_stack.is_empty());
+        wheel.apply_weight_preset("flat");
+        assert!(wheel.data.items.iter().all(|item| item.weight == 5));
+        assert!(wheel.undo_edit());
+        let weights: Vec<u32> = wheel.data.items.iter().map(|item| item.weight).collect();
+        assert_eq!(weights, [1, 2, 3]);
+    }
+
+    #[test]
+    fn imports_skip_duplicates_too() {
+        let mut wheel = wheel_with_weights(&[]);
+        wheel.add_item_with_weight("Pizza".to_string(), 1);
+        let (csv_items, bad_rows) = Wheel::from_csv("name,weight\npizza,2\ntacos,3\ntacos,1\n");
+        assert_eq!(bad_rows, 0);
+        assert_eq!(wheel.add_whole_items(csv_items), (1, 2));
+        assert_eq!(wheel.import_json(r#"[{"name": "sushi", "weight": 2}, {"name": " TACOS", "weight": 5}]"#), Ok((1, 1)));
+        let names: Vec<&str> = wheel.data.items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["Pizza", "tacos", "sushi"]);
+        assert_eq!(wheel.state.pct_bufs.len(), 3);
+    }
+
+    #[test]
+    fn new_wheel_from_removed_leaves_out_duplicates() {
+        let mut wheel = wheel_with_weights(&[]);
+        wheel.data.allow_duplicates = true;
+        wheel.add_items_bulk("pizza\npizza\ntacos");
+        for _ in 0..3 {
+            wheel.move_to_removed(0);
+        }
+        let mut app = WheelApp::new_for_test(vec![wheel], 1);
+        app.new_wheel_from_removed(true);
+        assert_eq!(app.current, 1);
+        assert_eq!(app.wheels[1].data.items.len(), 2);
+        assert!(app.wheels[0].data.removed_items.is_empty());
     }
-}
\ No newline at end of file
+
+    // the slice drawn under the arrow worked out the way the painter does it: each slice
+    // covers screen angles from span_start - rotation round to span_end - rotation
+    fn item_drawn_under_pointer(wheel: &Wheel) -> usize {
+        let pointer_angle = wheel.data.pointer_pos.angle();
+        let pointer_point = egui::vec2(pointer_angle.cos(), pointer_angle.sin());
+        let pointer_screen_angle = pointer_point.y.atan2(pointer_point.x);
+        let spans = wheel.slice_spans(&wheel.display_weights());
+        spans
+            .iter()
+            .find(|&&(_, span_start, span_end)| {
+                let into_slice = (pointer_screen_angle - (span_start - wheel.state.rotation)).rem_euclid(2.0 * PI);
+                into_slice < span_end - span_start
+            })
+            .map(|&(item_index, _, _)| item_index)
+            .expect("the pointer is always over some slice")
+    }
+
+    #[test]
+    fn winner_is_the_slice_under_the_arrow() {
+        let mut spin_rng = SpinRng::default();
+        for pointer_pos in [PointerPos::Top, PointerPos::Right, PointerPos::Bottom, PointerPos::Left] {
+            for clockwise in [false, true] {
+                let mut wheel = wheel_with_weights(&[1, 2, 3, 5, 8]);
+                wheel.data.pointer_pos = pointer_pos;
+                wheel.data.clockwise = clockwise;
+                // step a spin along by hand so rotation goes whichever way the wheel turns
+                wheel.state.is_spinning = true;
+                wheel.state.velocity = 0.3;
+                for _ in 0..60 {
+                    wheel.tick(1.0 / 60.0, &mut spin_rng);
+                    // stay clear of the slice edges, float rounding can go either way there
+                    let spans = wheel.slice_spans(&wheel.display_weights());
+                    let span_angle = (pointer_pos.angle() + wheel.state.rotation).rem_euclid(2.0 * PI);
+                    if spans.iter().any(|&(_, span_start, _)| (span_angle - span_start).abs() < 1e-3) {
+                        continue;
+                    }
+                    assert_eq!(wheel.get_winner(), item_drawn_under_pointer(&wheel), "{} clockwise {}", pointer_pos.label(), clockwise);
+                }
+                assert_eq!(wheel.state.rotation < 0.0, clockwise);
+            }
+        }
+    }
+
+    fn elimination_wheel(weights: &[u32]) -> Wheel {
+        let mut wheel = wheel_with_weights(weights);
+        wheel.data.auto_spin = true;
+        wheel.data.remove_winner = true;
+        wheel
+    }
+
+    #[test]
+    fn elimination_chain_stops_at_one_item() {
+        for seed in 0..20 {
+            let mut spin_rng = SpinRng { mode: RngMode::Sequence, base_seed: seed, state: seed };
+            let mut wheel = elimination_wheel(&[1, 2, 3, 4, 5, 6]);
+            wheel.spin(&mut spin_rng);
+            assert!(wheel.run_spin_to_end(&mut spin_rng));
+            assert_eq!(wheel.data.items.len(), 1);
+            assert_eq!(wheel.data.removed_items.len(), 5);
+            assert_eq!(wheel.data.winner_history.len(), 5);
+            assert!(!wheel.state.is_spinning);
+        }
+    }
+
+    #[test]
+    fn elimination_chain_stops_at_one_item_with_every_weight_zero() {
+        for seed in 0..20 {
+            let mut spin_rng = SpinRng { mode: RngMode::Sequence, base_seed: seed, state: seed };
+            let mut wheel = elimination_wheel(&[0, 0, 0, 0]);
+            wheel.spin(&mut spin_rng);
+            wheel.run_spin_to_end(&mut spin_rng);
+            assert_eq!(wheel.data.items.len(), 1);
+            assert!(!wheel.state.is_spinning);
+        }
+    }
+
+    fn test_app(weights: &[u32], seed: u64) -> WheelApp {
+        WheelApp::new_for_test(vec![wheel_with_weights(weights)], seed)
+    }
+
+    fn spin_test_app(app: &mut WheelApp) -> bool {
+        app.run_command(Command::Spin);
+        app.advance_current_wheel(0.0)
+    }
+
+    #[test]
+    fn test_mode_spin_moves_the_winner_to_removed_and_history() {
+        let mut app = test_app(&[1, 1, 1, 1], 11);
+        app.wheels[0].data.remove_winner = true;
+        assert!(spin_test_app(&mut app));
+        let wheel = &app.wheels[0];
+        assert_eq!(wheel.data.items.len(), 3);
+        assert_eq!(wheel.data.removed_items.len(), 1);
+        assert_eq!(wheel.data.winner_history.len(), 1);
+        assert_eq!(wheel.data.winner_history[0].name, wheel.data.removed_items[0].name);
+        assert!(!wheel.state.is_spinning);
+    }
+
+    #[test]
+    fn test_mode_auto_spin_runs_down_to_one() {
+        let mut app = test_app(&[3, 1, 4, 1, 5], 5);
+        app.wheels[0].data.remove_winner = true;
+        app.wheels[0].data.auto_spin = true;
+        assert!(spin_test_app(&mut app));
+        assert_eq!(app.wheels[0].data.items.len(), 1);
+        assert_eq!(app.wheels[0].data.winner_history.len(), 4);
+    }
+
+    #[test]
+    fn test_mode_same_seed_same_history() {
+        let history_for = |seed: u64| {
+            let mut app = test_app(&[1, 2, 3, 4, 5], seed);
+            for _ in 0..10 {
+                spin_test_app(&mut app);
+            }
+            app.wheels[0].data.winner_history.iter().map(|entry| entry.name.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(history_for(42), history_for(42));
+        assert_eq!(history_for(42).len(), 10);
+    }
+
+    #[test]
+    fn test_mode_cooldown_cycles_through_everything() {
+        let mut app = test_app(&[1, 1, 1], 8);
+        app.wheels[0].data.winner_cooldown = 2;
+        // each winner sits out the next two spins, so every three spins is one of each
+        for _ in 0..4 {
+            let mut winners: Vec<String> = (0..3)
+                .map(|_| {
+                    spin_test_app(&mut app);
+                    app.wheels[0].data.winner_history[0].name.clone()
+                })
+                .collect();
+            winners.sort();
+            assert_eq!(winners, ["item 0", "item 1", "item 2"]);
+        }
+    }
+
+    #[test]
+    fn test_mode_does_not_write_files() {
+        let mut app = test_app(&[1, 1], 1);
+        assert!(app.wheels[0].certified_draw(&mut app.spin_rng, true).is_err());
+        assert!(app.wheels[0].data.winner_history.is_empty());
+        assert!(write_export(true, "Test", "md", "nothing").is_err());
+        assert!(write_app_file(true, Path::new("never-written.txt"), b"nothing").is_err());
+        assert!(!Path::new("never-written.txt").exists());
+        let (sender, _receiver) = mpsc::channel();
+        let gif_frame = GifFrame { width: 1, height: 1, rgba: vec![0, 0, 0, 255] };
+        assert!(encode_gif(true, vec![gif_frame], 10, PathBuf::from("never-written.gif"), &sender).is_err());
+        assert!(!Path::new("never-written.gif").exists());
+        assert!(app.delete_profile("Someone").is_err());
+    }
+
+    #[test]
+    fn certificate_hash_matches_its_own_items() {
+        let mut app = test_app(&[1, 2, 3], 4);
+        // a cooldown changes the draw weights, the hash has to follow what's printed
+        app.wheels[0].data.items[2].cooldown_left = 1;
+        let certificate = app.wheels[0].draw_certificate(&mut app.spin_rng).unwrap();
+        assert_eq!(certificate.items_sha256, hash_items(&certificate.items));
+        assert_eq!(certificate.items[2].weight, 0);
+    }
+
+    #[test]
+    fn certificate_seed_follows_the_wheel_seed() {
+        let mut first = test_app(&[1, 1, 1, 1], 1);
+        let mut second = test_app(&[1, 1, 1, 1], 2);
+        first.wheels[0].data.seed = Some(99);
+        second.wheels[0].data.seed = Some(99);
+        for _ in 0..5 {
+            let a = first.wheels[0].draw_certificate(&mut first.spin_rng).unwrap();
+            let b = second.wheels[0].draw_certificate(&mut second.spin_rng).unwrap();
+            assert_eq!((a.seed, a.winner_index), (b.seed, b.winner_index));
+            // redoing the pick from the certificate alone lands on the same item
+            let mut rng = ChaCha20Rng::seed_from_u64(a.seed);
+            let total: u64 = a.items.iter().map(|item| item.weight as u64).sum();
+            let mut roll = rng.gen_range(0..total);
+            let redone = a.items.iter().position(|item| {
+                let inside = roll < item.weight as u64;
+                roll = roll.saturating_sub(item.weight as u64);
+                inside
+            });
+            assert_eq!(redone, Some(a.winner_index));
+        }
+    }
+
+    #[test]
+    fn undo_spin_takes_back_its_own_history_entry() {
+        let mut app = test_app(&[1, 1, 1, 1], 5);
+        app.wheels[0].data.remove_winner = true;
+        spin_test_app(&mut app);
+        let winner = app.wheels[0].data.winner_history[0].name.clone();
+        // something newer on top that the undo shouldn't touch
+        app.wheels[0].data.winner_history.insert(0, HistoryEntry::now("someone else".to_string()));
+        assert!(app.wheels[0].undo_spin().is_ok());
+        let wheel = &app.wheels[0];
+        assert_eq!(wheel.data.winner_history.len(), 1);
+        assert_eq!(wheel.data.winner_history[0].name, "someone else");
+        assert!(wheel.has_item_named(&winner));
+        assert!(wheel.data.removed_items.is_empty());
+    }
+
+    #[test]
+    fn undo_spin_refuses_after_the_history_is_cleared() {
+        let mut app = test_app(&[1, 1, 1, 1], 5);
+        app.wheels[0].data.remove_winner = true;
+        spin_test_app(&mut app);
+        app.wheels[0].data.winner_history.clear();
+        assert!(app.wheels[0].undo_spin().is_err());
+        let wheel = &app.wheels[0];
+        assert_eq!(wheel.data.items.len(), 3);
+        assert_eq!(wheel.data.removed_items.len(), 1);
+        assert_eq!(wheel.data.removed_items[0].win_count, 1);
+        assert!(wheel.state.spin_undo.is_empty());
+    }
+
+    #[test]
+    fn instant_draws_wait_for_a_pending_result() {
+        let mut app = test_app(&[1, 1, 1], 13);
+        app.wheels[0].data.confirm_winner = true;
+        app.wheels[0].data.items[0].selected = true;
+        spin_test_app(&mut app);
+        assert!(app.wheels[0].state.provisional_winner.is_some());
+        assert!(app.wheels[0].spin_selected(&mut app.spin_rng).is_none());
+        assert!(app.wheels[0].certified_draw(&mut app.spin_rng, true).is_err());
+        assert!(app.wheels[0].decide_now(&mut app.spin_rng).is_none());
+        assert!(app.wheels[0].data.winner_history.is_empty());
+        assert!(app.wheels[0].state.spin_undo.is_empty());
+    }
+
+    #[test]
+    fn spin_selected_can_be_undone() {
+        let mut app = test_app(&[1, 1, 1, 1], 6);
+        app.wheels[0].data.items[0].win_count = 2;
+        app.wheels[0].data.items[0].selected = true;
+        app.wheels[0].data.items[1].selected = true;
+        let winner = app.wheels[0].spin_selected(&mut app.spin_rng).unwrap();
+        assert!(winner == "item 0" || winner == "item 1");
+        let history_entry = &app.wheels[0].data.winner_history[0];
+        // bare name so chips, pins and stats still find the item
+        assert_eq!(history_entry.name, winner);
+        assert_eq!(history_entry.label(), format!("{} (subset of 2)", winner));
+        assert_eq!(app.wheels[0].state.spin_undo.len(), 1);
+        // a plain result with the same name on top isn't the one to take back
+        app.wheels[0].data.winner_history.insert(0, HistoryEntry::now(winner.clone()));
+        assert!(app.wheels[0].undo_spin().is_ok());
+        let wheel = &app.wheels[0];
+        assert_eq!(wheel.data.winner_history.len(), 1);
+        assert_eq!(wheel.data.winner_history[0].subset_of, None);
+        // spin selected never counted the win, so the undo leaves the count alone
+        assert_eq!(wheel.data.items[0].win_count, 2);
+    }
+
+    #[test]
+    fn recorded_spin_selected_replays_the_same() {
+        let mut app = test_app(&[1, 2, 3, 4], 7);
+        app.wheels[0].start_event_recording();
+        spin_test_app(&mut app);
+        for item in app.wheels[0].data.items.iter_mut().skip(1) {
+            item.selected = true;
+        }
+        for _ in 0..3 {
+            app.wheels[0].spin_selected(&mut app.spin_rng).unwrap();
+        }
+        spin_test_app(&mut app);
+        let event_archive = app.wheels[0].state.event_recording.take().unwrap();
+        assert_eq!(event_archive.spins.len(), 5);
+        assert_eq!(verify_event_archive(&event_archive), (5, 0));
+    }
+
+    #[test]
+    fn top_weight_ties_counts_only_real_slices() {
+        assert_eq!(wheel_with_weights(&[0, 0, 0]).top_weight_ties(), 0);
+        assert_eq!(wheel_with_weights(&[]).top_weight_ties(), 0);
+        assert_eq!(wheel_with_weights(&[2, 0, 2, 1]).top_weight_ties(), 2);
+    }
+
+    #[test]
+    fn clear_history_and_reset_stats_ask_first() {
+        let mut app = test_app(&[1, 1, 1], 9);
+        spin_test_app(&mut app);
+        app.run_command(Command::ClearHistory);
+        assert!(app.pending_action == Some(PendingAction::ClearHistory));
+        assert_eq!(app.wheels[0].data.winner_history.len(), 1);
+        app.perform_action(PendingAction::ClearHistory);
+        assert!(app.wheels[0].data.winner_history.is_empty());
+        app.pending_action = None;
+        app.request_action(PendingAction::ResetStats);
+        assert_eq!(app.wheels[0].data.items.iter().map(|item| item.win_count).sum::<u32>(), 1);
+        app.perform_action(PendingAction::ResetStats);
+        assert!(app.wheels[0].data.items.iter().all(|item| item.win_count == 0));
+    }
+
+    #[test]
+    fn palette_edits_drop_the_undo_clear_stash() {
+        let mut app = test_app(&[1, 1, 1], 10);
+        app.run_command(Command::ClearAll);
+        let pending_action = app.pending_action.take().unwrap();
+        app.perform_action(pending_action);
+        assert!(app.wheels[0].state.cleared_stash.is_some());
+        app.run_command(Command::UndoClear);
+        assert_eq!(app.wheels[0].data.items.len(), 3);
+
+        app.run_command(Command::ClearAll);
+        let pending_action = app.pending_action.take().unwrap();
+        app.perform_action(pending_action);
+        app.run_command(Command::ToggleHeatmap);
+        assert!(app.wheels[0].state.cleared_stash.is_none());
+        app.run_command(Command::UndoClear);
+        assert!(app.wheels[0].data.items.is_empty());
+    }
+
+    #[test]
+    fn recorded_certified_draw_replays_the_same() {
+        let mut app = test_app(&[1, 2, 3, 4], 12);
+        app.wheels[0].data.pick_count = 2;
+        app.wheels[0].start_event_recording();
+        spin_test_app(&mut app);
+        for _ in 0..3 {
+            // what certified_draw does once the certificate is written, test mode won't write it
+            let certificate = app.wheels[0].draw_certificate(&mut app.spin_rng).unwrap();
+            app.wheels[0].finish_certified_draw(certificate.seed, certificate.winner_index);
+        }
+        app.run_command(Command::DecideNow);
+        let event_archive = app.wheels[0].state.event_recording.take().unwrap();
+        assert_eq!(event_archive.spins.iter().filter(|archived_spin| archived_spin.certified).count(), 3);
+        assert_eq!(verify_event_archive(&event_archive), (5, 0));
+    }
+
+    #[test]
+    fn stop_ends_the_chain_without_a_result() {
+        let mut spin_rng = SpinRng { mode: RngMode::Sequence, base_seed: 3, state: 3 };
+        let mut wheel = elimination_wheel(&[1, 1, 1, 1]);
+        wheel.spin(&mut spin_rng);
+        wheel.tick(1.0, &mut spin_rng);
+        wheel.stop_spinning();
+        assert!(!wheel.run_spin_to_end(&mut spin_rng));
+        assert_eq!(wheel.data.items.len(), 4);
+        assert!(wheel.data.winner_history.is_empty());
+    }
+}